@@ -1,4 +1,4 @@
-use crate::device::Device;
+use crate::device::Transport;
 use crate::packet::Packet;
 use crate::types::{
     BatteryCare, Cluster, CpuBoost, FanMode, FanZone, GpuBoost, LightsAlwaysOn, LogoMode,
@@ -7,13 +7,13 @@ use crate::types::{
 
 use anyhow::{bail, ensure, Result};
 
-fn _send_command(device: &Device, command: u16, args: &[u8]) -> Result<Packet> {
+fn _send_command(device: &impl Transport, command: u16, args: &[u8]) -> Result<Packet> {
     let response = device.send(Packet::new(command, args))?;
     ensure!(response.get_args().starts_with(args));
     Ok(response)
 }
 
-fn _set_perf_mode(device: &Device, perf_mode: PerfMode, fan_mode: FanMode) -> Result<()> {
+fn _set_perf_mode(device: &impl Transport, perf_mode: PerfMode, fan_mode: FanMode) -> Result<()> {
     if (fan_mode == FanMode::Manual) && (perf_mode != PerfMode::Balanced) {
         bail!("{:?} allowed only in {:?}", fan_mode, PerfMode::Balanced);
     }
@@ -28,7 +28,7 @@ fn _set_perf_mode(device: &Device, perf_mode: PerfMode, fan_mode: FanMode) -> Re
     })
 }
 
-fn _set_boost(device: &Device, cluster: Cluster, boost: u8) -> Result<()> {
+fn _set_boost(device: &impl Transport, cluster: Cluster, boost: u8) -> Result<()> {
     let args = &[0, cluster as u8, boost];
     ensure!(
         get_perf_mode(device)? == (PerfMode::Custom, FanMode::Auto),
@@ -42,17 +42,17 @@ fn _set_boost(device: &Device, cluster: Cluster, boost: u8) -> Result<()> {
     Ok(())
 }
 
-fn _get_boost(device: &Device, cluster: Cluster) -> Result<u8> {
+fn _get_boost(device: &impl Transport, cluster: Cluster) -> Result<u8> {
     let response = device.send(Packet::new(0x0d87, &[0, cluster as u8, 0]))?;
     ensure!(response.get_args()[1] == cluster as u8);
     Ok(response.get_args()[2])
 }
 
-pub fn set_perf_mode(device: &Device, perf_mode: PerfMode) -> Result<()> {
+pub fn set_perf_mode(device: &impl Transport, perf_mode: PerfMode) -> Result<()> {
     _set_perf_mode(device, perf_mode, FanMode::Auto)
 }
 
-pub fn get_perf_mode(device: &Device) -> Result<(PerfMode, FanMode)> {
+pub fn get_perf_mode(device: &impl Transport) -> Result<(PerfMode, FanMode)> {
     let [r1, r2]: [Result<(PerfMode, FanMode)>; 2] = [1, 2].map(|zone| {
         let response = device.send(Packet::new(0x0d82, &[0, zone, 0, 0]))?;
         Ok((
@@ -77,23 +77,23 @@ pub fn get_perf_mode(device: &Device) -> Result<(PerfMode, FanMode)> {
     Ok(r1)
 }
 
-pub fn set_cpu_boost(device: &Device, boost: CpuBoost) -> Result<()> {
+pub fn set_cpu_boost(device: &impl Transport, boost: CpuBoost) -> Result<()> {
     _set_boost(device, Cluster::Cpu, boost as u8)
 }
 
-pub fn set_gpu_boost(device: &Device, boost: GpuBoost) -> Result<()> {
+pub fn set_gpu_boost(device: &impl Transport, boost: GpuBoost) -> Result<()> {
     _set_boost(device, Cluster::Gpu, boost as u8)
 }
 
-pub fn get_cpu_boost(device: &Device) -> Result<CpuBoost> {
+pub fn get_cpu_boost(device: &impl Transport) -> Result<CpuBoost> {
     CpuBoost::try_from(_get_boost(device, Cluster::Cpu)?)
 }
 
-pub fn get_gpu_boost(device: &Device) -> Result<GpuBoost> {
+pub fn get_gpu_boost(device: &impl Transport) -> Result<GpuBoost> {
     GpuBoost::try_from(_get_boost(device, Cluster::Gpu)?)
 }
 
-pub fn set_fan_rpm(device: &Device, rpm: u16) -> Result<()> {
+pub fn set_fan_rpm(device: &impl Transport, rpm: u16) -> Result<()> {
     ensure!((2000..=5000).contains(&rpm));
     ensure!(
         get_perf_mode(device)? == (PerfMode::Balanced, FanMode::Manual),
@@ -108,13 +108,13 @@ pub fn set_fan_rpm(device: &Device, rpm: u16) -> Result<()> {
         })
 }
 
-pub fn get_fan_rpm(device: &Device, fan_zone: FanZone) -> Result<u16> {
+pub fn get_fan_rpm(device: &impl Transport, fan_zone: FanZone) -> Result<u16> {
     let response = device.send(Packet::new(0x0d81, &[0, fan_zone as u8, 0]))?;
     ensure!(response.get_args()[1] == fan_zone as u8);
     Ok(response.get_args()[2] as u16 * 100)
 }
 
-pub fn set_max_fan_speed_mode(device: &Device, mode: MaxFanSpeedMode) -> Result<()> {
+pub fn set_max_fan_speed_mode(device: &impl Transport, mode: MaxFanSpeedMode) -> Result<()> {
     ensure!(
         get_perf_mode(device)?.0 == PerfMode::Custom,
         "Performance mode must be {:?}",
@@ -123,7 +123,11 @@ pub fn set_max_fan_speed_mode(device: &Device, mode: MaxFanSpeedMode) -> Result<
     _send_command(device, 0x070f, &[mode as u8]).map(|_| ())
 }
 
-pub fn set_fan_mode(device: &Device, mode: FanMode) -> Result<()> {
+pub fn get_max_fan_speed_mode(device: &impl Transport) -> Result<MaxFanSpeedMode> {
+    device.send(Packet::new(0x078f, &[0]))?.get_args()[0].try_into()
+}
+
+pub fn set_fan_mode(device: &impl Transport, mode: FanMode) -> Result<()> {
     ensure!(
         get_perf_mode(device)?.0 == PerfMode::Balanced,
         "Performance mode must be {:?}",
@@ -132,22 +136,21 @@ pub fn set_fan_mode(device: &Device, mode: FanMode) -> Result<()> {
     _set_perf_mode(device, PerfMode::Balanced, mode)
 }
 
-pub fn custom_command(device: &Device, command: u16, args: &[u8]) -> Result<()> {
-    let report = Packet::new(command, args);
-    println!("Report   {:?}", report);
-    let response = device.send(report)?;
-    println!("Response {:?}", response);
+pub fn custom_command(device: &impl Transport, command: u16, args: &[u8]) -> Result<()> {
+    println!("Report   {:04x} {:02x?}", command, args);
+    let response = device.send(Packet::new(command, args))?;
+    println!("Response {:04x} {:02x?}", response.command(), response.get_args());
     Ok(())
 }
 
-fn _set_logo_power(device: &Device, mode: LogoMode) -> Result<Packet> {
+fn _set_logo_power(device: &impl Transport, mode: LogoMode) -> Result<Packet> {
     match mode {
         LogoMode::Off => _send_command(device, 0x0300, &[1, 4, 0]),
         LogoMode::Static | LogoMode::Breathing => _send_command(device, 0x0300, &[1, 4, 1]),
     }
 }
 
-fn _set_logo_mode(device: &Device, mode: LogoMode) -> Result<Packet> {
+fn _set_logo_mode(device: &impl Transport, mode: LogoMode) -> Result<Packet> {
     match mode {
         LogoMode::Static => _send_command(device, 0x0302, &[1, 4, 0]),
         LogoMode::Breathing => _send_command(device, 0x0302, &[1, 4, 2]),
@@ -155,7 +158,7 @@ fn _set_logo_mode(device: &Device, mode: LogoMode) -> Result<Packet> {
     }
 }
 
-fn _get_logo_power(device: &Device) -> Result<bool> {
+fn _get_logo_power(device: &impl Transport) -> Result<bool> {
     match device.send(Packet::new(0x0380, &[1, 4, 0]))?.get_args()[2] {
         0 => Ok(false),
         1 => Ok(true),
@@ -163,7 +166,7 @@ fn _get_logo_power(device: &Device) -> Result<bool> {
     }
 }
 
-fn _get_logo_mode(device: &Device) -> Result<LogoMode> {
+fn _get_logo_mode(device: &impl Transport) -> Result<LogoMode> {
     match device.send(Packet::new(0x0382, &[1, 4, 0]))?.get_args()[2] {
         0 => Ok(LogoMode::Static),
         2 => Ok(LogoMode::Breathing),
@@ -171,7 +174,7 @@ fn _get_logo_mode(device: &Device) -> Result<LogoMode> {
     }
 }
 
-pub fn get_logo_mode(device: &Device) -> Result<LogoMode> {
+pub fn get_logo_mode(device: &impl Transport) -> Result<LogoMode> {
     let power = _get_logo_power(device)?;
     match power {
         true => _get_logo_mode(device),
@@ -179,7 +182,7 @@ pub fn get_logo_mode(device: &Device) -> Result<LogoMode> {
     }
 }
 
-pub fn set_logo_mode(device: &Device, mode: LogoMode) -> Result<()> {
+pub fn set_logo_mode(device: &impl Transport, mode: LogoMode) -> Result<()> {
     if mode != LogoMode::Off {
         _set_logo_mode(device, mode)?;
     }
@@ -187,13 +190,13 @@ pub fn set_logo_mode(device: &Device, mode: LogoMode) -> Result<()> {
     Ok(())
 }
 
-pub fn get_keyboard_brightness(device: &Device) -> Result<u8> {
+pub fn get_keyboard_brightness(device: &impl Transport) -> Result<u8> {
     let response = device.send(Packet::new(0x0383, &[1, 5, 0]))?;
     ensure!(response.get_args()[1] == 5);
     Ok(response.get_args()[2])
 }
 
-pub fn set_keyboard_brightness(device: &Device, brightness: u8) -> Result<()> {
+pub fn set_keyboard_brightness(device: &impl Transport, brightness: u8) -> Result<()> {
     let args = &[1, 5, brightness];
     ensure!(device
         .send(Packet::new(0x0303, args))?
@@ -202,11 +205,14 @@ pub fn set_keyboard_brightness(device: &Device, brightness: u8) -> Result<()> {
     Ok(())
 }
 
-pub fn get_lights_always_on(device: &Device) -> Result<LightsAlwaysOn> {
+pub fn get_lights_always_on(device: &impl Transport) -> Result<LightsAlwaysOn> {
     device.send(Packet::new(0x0084, &[0, 0]))?.get_args()[0].try_into()
 }
 
-pub fn set_lights_always_on(device: &Device, lights_always_on: LightsAlwaysOn) -> Result<()> {
+pub fn set_lights_always_on(
+    device: &impl Transport,
+    lights_always_on: LightsAlwaysOn,
+) -> Result<()> {
     let args = &[lights_always_on as u8, 0];
     ensure!(device
         .send(Packet::new(0x0004, args))?
@@ -215,11 +221,11 @@ pub fn set_lights_always_on(device: &Device, lights_always_on: LightsAlwaysOn) -
     Ok(())
 }
 
-pub fn get_battery_care(device: &Device) -> Result<BatteryCare> {
+pub fn get_battery_care(device: &impl Transport) -> Result<BatteryCare> {
     device.send(Packet::new(0x0792, &[0]))?.get_args()[0].try_into()
 }
 
-pub fn set_battery_care(device: &Device, mode: BatteryCare) -> Result<()> {
+pub fn set_battery_care(device: &impl Transport, mode: BatteryCare) -> Result<()> {
     let args = &[mode as u8];
     ensure!(device
         .send(Packet::new(0x0712, args))?
@@ -227,3 +233,129 @@ pub fn set_battery_care(device: &Device, mode: BatteryCare) -> Result<()> {
         .starts_with(args));
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// One queued (command, args-prefix) -> response-args exchange.
+    struct Expectation {
+        command: u16,
+        args_prefix: Vec<u8>,
+        response_args: Vec<u8>,
+    }
+
+    #[derive(Default)]
+    struct MockTransport {
+        expectations: RefCell<VecDeque<Expectation>>,
+    }
+
+    impl MockTransport {
+        fn expect(self, command: u16, args_prefix: &[u8], response_args: &[u8]) -> Self {
+            self.expectations.borrow_mut().push_back(Expectation {
+                command,
+                args_prefix: args_prefix.to_vec(),
+                response_args: response_args.to_vec(),
+            });
+            self
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn send(&self, report: Packet) -> Result<Packet> {
+            let expectation = self
+                .expectations
+                .borrow_mut()
+                .pop_front()
+                .expect("unexpected send: no more expectations queued");
+            assert_eq!(expectation.command, report.command(), "unexpected command");
+            assert!(
+                report.get_args().starts_with(&expectation.args_prefix),
+                "unexpected args: {:?}",
+                report.get_args()
+            );
+            Ok(report.reply(&expectation.response_args))
+        }
+    }
+
+    fn perf_mode_response(zone: u8, perf_mode: PerfMode, fan_mode: FanMode) -> Vec<u8> {
+        vec![0, zone, perf_mode as u8, fan_mode as u8]
+    }
+
+    #[test]
+    fn set_fan_rpm_rejects_outside_balanced_manual() {
+        let device = MockTransport::default()
+            .expect(
+                0x0d82,
+                &[0, 1, 0, 0],
+                &perf_mode_response(1, PerfMode::Custom, FanMode::Auto),
+            )
+            .expect(
+                0x0d82,
+                &[0, 2, 0, 0],
+                &perf_mode_response(2, PerfMode::Custom, FanMode::Auto),
+            );
+
+        assert!(set_fan_rpm(&device, 3000).is_err());
+    }
+
+    #[test]
+    fn set_fan_rpm_applies_in_balanced_manual() {
+        let device = MockTransport::default()
+            .expect(
+                0x0d82,
+                &[0, 1, 0, 0],
+                &perf_mode_response(1, PerfMode::Balanced, FanMode::Manual),
+            )
+            .expect(
+                0x0d82,
+                &[0, 2, 0, 0],
+                &perf_mode_response(2, PerfMode::Balanced, FanMode::Manual),
+            )
+            .expect(0x0d01, &[0, 1, 30], &[0, 1, 30])
+            .expect(0x0d01, &[0, 2, 30], &[0, 2, 30]);
+
+        assert!(set_fan_rpm(&device, 3000).is_ok());
+    }
+
+    #[test]
+    fn set_cpu_boost_requires_custom_and_auto_fan() {
+        let device = MockTransport::default()
+            .expect(
+                0x0d82,
+                &[0, 1, 0, 0],
+                &perf_mode_response(1, PerfMode::Balanced, FanMode::Auto),
+            )
+            .expect(
+                0x0d82,
+                &[0, 2, 0, 0],
+                &perf_mode_response(2, PerfMode::Balanced, FanMode::Auto),
+            );
+
+        assert!(set_cpu_boost(&device, CpuBoost::Boost).is_err());
+    }
+
+    #[test]
+    fn set_cpu_boost_applies_in_custom_mode() {
+        let device = MockTransport::default()
+            .expect(
+                0x0d82,
+                &[0, 1, 0, 0],
+                &perf_mode_response(1, PerfMode::Custom, FanMode::Auto),
+            )
+            .expect(
+                0x0d82,
+                &[0, 2, 0, 0],
+                &perf_mode_response(2, PerfMode::Custom, FanMode::Auto),
+            )
+            .expect(
+                0x0d07,
+                &[0, Cluster::Cpu as u8, CpuBoost::Boost as u8],
+                &[0, Cluster::Cpu as u8, CpuBoost::Boost as u8],
+            );
+
+        assert!(set_cpu_boost(&device, CpuBoost::Boost).is_ok());
+    }
+}
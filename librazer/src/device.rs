@@ -1,12 +1,21 @@
 use crate::descriptor::{Descriptor, SUPPORTED};
 use crate::packet::Packet;
+use crate::trace::{Capture, Direction};
 
 use anyhow::{anyhow, Context, Result};
 use std::{thread, time};
 
+/// Transport abstracts the byte-level exchange of a [`Packet`] with a Razer device, so the
+/// command layer in [`crate::command`] can be exercised against a recorded-packet mock instead
+/// of real hardware.
+pub trait Transport {
+    fn send(&self, report: Packet) -> Result<Packet>;
+}
+
 pub struct Device {
     device: hidapi::HidDevice,
     pub info: Descriptor,
+    capture: Option<Capture>,
 }
 
 // Read the model id and clip to conform with https://mysupport.razer.com/app/answers/detail/a_id/5481
@@ -18,10 +27,28 @@ fn read_device_model() -> Result<String> {
         let system_sku: String = bios.get_value("SystemSKU")?;
         Ok(system_sku.chars().take(10).collect())
     }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        let system_sku = std::fs::read_to_string("/sys/class/dmi/id/product_sku")
+            .context("Failed to read /sys/class/dmi/id/product_sku")?;
+        Ok(system_sku.trim().chars().take(10).collect())
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     anyhow::bail!("Automatic model detection is not implemented for this platform")
 }
 
+/// A model number prefix wasn't found in [`SUPPORTED`], but the razer-ctl authors would still
+/// like to hear about the device. Exposes no features by default: a user can still reach `info`,
+/// `profile`, `daemon`/`replay`, and the raw `cmd` escape hatch to probe the hardware safely.
+fn generic_descriptor(pid: u16) -> Descriptor {
+    Descriptor {
+        model_number_prefix: "Unknown",
+        name: "Unknown Razer device",
+        pid,
+        features: &[],
+    }
+}
+
 impl Device {
     const RAZER_VID: u16 = 0x1532;
 
@@ -42,17 +69,29 @@ impl Device {
                 return Ok(Device {
                     device,
                     info: descriptor.clone(),
+                    capture: None,
                 });
             }
         }
         anyhow::bail!("Failed to open device {:?}", descriptor)
     }
 
+    /// Opt in to appending every sent/received packet to `path` as JSONL, for
+    /// reverse-engineering unsupported models. See [`crate::trace`].
+    pub fn enable_capture(&mut self, path: &std::path::Path) -> Result<()> {
+        self.capture = Some(Capture::open(path)?);
+        Ok(())
+    }
+
     pub fn send(&self, report: Packet) -> Result<Packet> {
         // extra byte for report id
         let mut response_buf: Vec<u8> = vec![0x00; 1 + std::mem::size_of::<Packet>()];
         //println!("Report {:?}", report);
 
+        if let Some(capture) = &self.capture {
+            capture.record(Direction::Sent, &report)?;
+        }
+
         thread::sleep(time::Duration::from_micros(1000));
         self.device
             .send_feature_report(
@@ -73,6 +112,11 @@ impl Device {
         // skip report id byte
         let response = <&[u8] as TryInto<Packet>>::try_into(&response_buf[1..])?;
         //println!("Response {:?}", response);
+
+        if let Some(capture) = &self.capture {
+            capture.record(Direction::Received, &response)?;
+        }
+
         response.ensure_matches_report(&report)
     }
 
@@ -104,11 +148,24 @@ impl Device {
             .find(|supported| model_number_prefix.starts_with(supported.model_number_prefix))
         {
             Some(supported) => Device::new(supported.clone()),
-            None => anyhow::bail!(
-                "Model {} with PIDs {:0>4x?} is not supported",
-                model_number_prefix,
-                pid_list
-            ),
+            None => {
+                let pid = *pid_list
+                    .first()
+                    .context("No Razer devices found to fall back to")?;
+                eprintln!(
+                    "Model {} with PIDs {:0>4x?} is not in the supported list; falling back to \
+                     a generic descriptor with no features enabled. Please capture a trace \
+                     (razer-cli --capture trace.jsonl ...) and open an issue so it can be added.",
+                    model_number_prefix, pid_list
+                );
+                Device::new(generic_descriptor(pid))
+            }
         }
     }
 }
+
+impl Transport for Device {
+    fn send(&self, report: Packet) -> Result<Packet> {
+        self.send(report)
+    }
+}
@@ -0,0 +1,281 @@
+//! Temperature-driven automatic fan control: a piecewise-linear curve with hysteresis, or a PID
+//! loop holding a fixed setpoint. Both modes take over `FanMode::Manual` for their lifetime and
+//! hand control back to `FanMode::Auto` when the caller clears `running` (e.g. on SIGINT).
+
+use crate::command;
+use crate::device::Transport;
+use crate::types::FanMode;
+
+use anyhow::{ensure, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+pub const MIN_RPM: u16 = 2000;
+pub const MAX_RPM: u16 = 5000;
+
+/// One `(temp_celsius, rpm)` control point of a fan curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint {
+    pub temp_celsius: f32,
+    pub rpm: u16,
+}
+
+/// Reads the hottest Linux thermal zone, as a stand-in for CPU package temperature.
+pub fn read_cpu_temperature() -> Result<f32> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut hottest: Option<f32> = None;
+        for entry in std::fs::read_dir("/sys/class/thermal")?.flatten() {
+            let millidegrees = match std::fs::read_to_string(entry.path().join("temp")) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            if let Ok(millidegrees) = millidegrees.trim().parse::<i32>() {
+                let celsius = millidegrees as f32 / 1000.0;
+                hottest = Some(hottest.map_or(celsius, |hottest: f32| hottest.max(celsius)));
+            }
+        }
+        hottest.ok_or_else(|| anyhow::anyhow!("No thermal zones found under /sys/class/thermal"))
+    }
+    #[cfg(not(target_os = "linux"))]
+    anyhow::bail!("CPU temperature reading is not implemented for this platform")
+}
+
+/// Interpolates the target RPM for `temp` along `curve` (sorted by `temp_celsius`), holding the
+/// first/last point's RPM below/above the curve's endpoints. Every result, including interpolated
+/// ones between points, is clamped to [`MIN_RPM`, `MAX_RPM`].
+pub fn interpolate(curve: &[CurvePoint], temp: f32) -> u16 {
+    let clamp = |rpm: u16| rpm.clamp(MIN_RPM, MAX_RPM);
+
+    if let Some(first) = curve.first().filter(|p| temp <= p.temp_celsius) {
+        return clamp(first.rpm);
+    }
+    if let Some(last) = curve.last().filter(|p| temp >= p.temp_celsius) {
+        return clamp(last.rpm);
+    }
+
+    for points in curve.windows(2) {
+        let (lo, hi) = (points[0], points[1]);
+        if (lo.temp_celsius..=hi.temp_celsius).contains(&temp) {
+            let span = hi.temp_celsius - lo.temp_celsius;
+            let fraction = if span > 0.0 {
+                (temp - lo.temp_celsius) / span
+            } else {
+                0.0
+            };
+            let rpm = lo.rpm as f32 + fraction * (hi.rpm as f32 - lo.rpm as f32);
+            return clamp(rpm.round() as u16);
+        }
+    }
+
+    clamp(MIN_RPM)
+}
+
+/// Runs the piecewise-linear curve loop until `running` is cleared. Hysteresis: the commanded
+/// RPM only changes once the new target drifts past `deadband_rpm`, to avoid audible oscillation
+/// around a curve breakpoint.
+pub fn run_curve(
+    device: &impl Transport,
+    curve: &[CurvePoint],
+    poll_interval: Duration,
+    deadband_rpm: u16,
+    verbose: bool,
+    running: &AtomicBool,
+) -> Result<()> {
+    ensure!(curve.len() >= 2, "A fan curve needs at least two points");
+
+    command::set_fan_mode(device, FanMode::Manual)?;
+    let mut commanded_rpm: Option<u16> = None;
+
+    while running.load(Ordering::SeqCst) {
+        let temp = read_cpu_temperature()?;
+        let target_rpm = interpolate(curve, temp);
+
+        let should_apply = match commanded_rpm {
+            Some(current) => current.abs_diff(target_rpm) > deadband_rpm,
+            None => true,
+        };
+
+        if should_apply {
+            if verbose {
+                println!("{:.1}°C -> {} RPM", temp, target_rpm);
+            }
+            command::set_fan_rpm(device, target_rpm)?;
+            commanded_rpm = Some(target_rpm);
+        }
+
+        std::thread::sleep(poll_interval);
+    }
+
+    command::set_fan_mode(device, FanMode::Auto)
+}
+
+/// Gains for [`run_pid`].
+#[derive(Debug, Clone, Copy)]
+pub struct PidGains {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+}
+
+/// Controller state, kept separate from [`PidGains`] so a fresh [`run_pid`] call always starts
+/// from a reset integral/derivative history even if the gains or setpoint haven't changed.
+struct PidState {
+    integral: f32,
+    previous_error: Option<f32>,
+}
+
+impl PidState {
+    fn new() -> Self {
+        Self {
+            integral: 0.0,
+            previous_error: None,
+        }
+    }
+
+    /// Advances by one `dt`-second tick, returning the clamped RPM output. Anti-windup: the
+    /// integral term only accumulates while the output isn't saturated.
+    fn step(&mut self, gains: PidGains, error: f32, dt: f32) -> u16 {
+        let derivative = match self.previous_error {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        };
+        self.previous_error = Some(error);
+
+        let unsaturated = gains.kp * error + gains.ki * self.integral + gains.kd * derivative;
+        let output = unsaturated.clamp(MIN_RPM as f32, MAX_RPM as f32);
+
+        if output == unsaturated {
+            self.integral += error * dt;
+        }
+
+        output.round() as u16
+    }
+}
+
+/// Runs a PID loop holding `setpoint_celsius` until `running` is cleared.
+pub fn run_pid(
+    device: &impl Transport,
+    gains: PidGains,
+    setpoint_celsius: f32,
+    poll_interval: Duration,
+    verbose: bool,
+    running: &AtomicBool,
+) -> Result<()> {
+    command::set_fan_mode(device, FanMode::Manual)?;
+    let mut state = PidState::new();
+    let dt = poll_interval.as_secs_f32();
+
+    while running.load(Ordering::SeqCst) {
+        let temp = read_cpu_temperature()?;
+        let rpm = state.step(gains, temp - setpoint_celsius, dt);
+
+        if verbose {
+            println!(
+                "{:.1}°C (target {:.1}°C) -> {} RPM",
+                temp, setpoint_celsius, rpm
+            );
+        }
+        command::set_fan_rpm(device, rpm)?;
+
+        std::thread::sleep(poll_interval);
+    }
+
+    command::set_fan_mode(device, FanMode::Auto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> Vec<CurvePoint> {
+        vec![
+            CurvePoint {
+                temp_celsius: 40.0,
+                rpm: 2500,
+            },
+            CurvePoint {
+                temp_celsius: 60.0,
+                rpm: 3500,
+            },
+            CurvePoint {
+                temp_celsius: 80.0,
+                rpm: 4500,
+            },
+        ]
+    }
+
+    #[test]
+    fn interpolate_below_first_point_clamps_to_first_rpm() {
+        assert_eq!(interpolate(&curve(), 20.0), 2500);
+    }
+
+    #[test]
+    fn interpolate_above_last_point_clamps_to_last_rpm() {
+        assert_eq!(interpolate(&curve(), 90.0), 4500);
+    }
+
+    #[test]
+    fn interpolate_midpoint_between_two_points() {
+        assert_eq!(interpolate(&curve(), 50.0), 3000);
+    }
+
+    #[test]
+    fn interpolate_exact_point_returns_its_rpm() {
+        assert_eq!(interpolate(&curve(), 60.0), 3500);
+    }
+
+    #[test]
+    fn interpolate_clamps_curve_output_to_min_max_rpm() {
+        let curve = vec![
+            CurvePoint {
+                temp_celsius: 40.0,
+                rpm: 1000,
+            },
+            CurvePoint {
+                temp_celsius: 80.0,
+                rpm: 6000,
+            },
+        ];
+        assert_eq!(interpolate(&curve, 40.0), MIN_RPM);
+        assert_eq!(interpolate(&curve, 80.0), MAX_RPM);
+    }
+
+    fn gains() -> PidGains {
+        PidGains {
+            kp: 100.0,
+            ki: 10.0,
+            kd: 0.0,
+        }
+    }
+
+    #[test]
+    fn pid_step_clamps_output_to_min_max_rpm() {
+        let mut state = PidState::new();
+        assert_eq!(state.step(gains(), -1000.0, 1.0), MIN_RPM);
+
+        let mut state = PidState::new();
+        assert_eq!(state.step(gains(), 1000.0, 1.0), MAX_RPM);
+    }
+
+    #[test]
+    fn pid_step_anti_windup_does_not_accumulate_integral_while_saturated() {
+        let mut state = PidState::new();
+        state.step(gains(), 1000.0, 1.0);
+        let integral_after_first_step = state.integral;
+
+        state.step(gains(), 1000.0, 1.0);
+        assert_eq!(state.integral, integral_after_first_step);
+    }
+
+    #[test]
+    fn pid_step_integral_accumulates_once_unsaturated() {
+        // kp * 25 = 2500 RPM, inside [MIN_RPM, MAX_RPM], so neither step saturates.
+        let mut state = PidState::new();
+        state.step(gains(), 25.0, 1.0);
+        assert_eq!(state.integral, 25.0);
+
+        state.step(gains(), 25.0, 1.0);
+        assert_eq!(state.integral, 50.0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Unix-socket transport that lets a daemon keep a single [`Device`](crate::device::Device)
+//! open and serialize access to it, instead of every invocation paying
+//! `Device::detect`/`Device::new`'s open cost and racing other callers on the single
+//! feature-report channel.
+//!
+//! The host and the client both speak [`Packet`] (already `Serialize`/`Deserialize`, see
+//! [`crate::packet`]) newline-delimited as JSON, so the client is just another
+//! [`Transport`](crate::device::Transport) impl and every function in [`crate::command`] works
+//! against it unchanged.
+
+use crate::packet::Packet;
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+
+/// Default location for the daemon's socket.
+pub fn default_socket_path() -> std::path::PathBuf {
+    std::env::temp_dir().join("razer-ctl.sock")
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+#[cfg(feature = "host")]
+pub mod host {
+    use super::*;
+    use crate::device::Device;
+    use std::os::unix::net::UnixListener;
+
+    /// Bind `socket_path` and serve client connections one at a time for as long as the process
+    /// runs, forwarding every request straight to `device.send`.
+    pub fn run(device: &Device, socket_path: &std::path::Path) -> Result<()> {
+        // A stale socket from a previous run would otherwise make bind fail.
+        let _ = std::fs::remove_file(socket_path);
+        let listener = UnixListener::bind(socket_path)
+            .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
+
+        for stream in listener.incoming() {
+            let stream = stream.context("Failed to accept client connection")?;
+            if let Err(e) = serve(device, stream) {
+                eprintln!("Client disconnected: {:?}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn serve(device: &Device, stream: UnixStream) -> Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+
+        while let Some(line) = read_line(&mut reader)? {
+            let report: Packet = serde_json::from_str(line.trim_end())?;
+            let response: std::result::Result<Packet, String> =
+                device.send(report).map_err(|e| format!("{:?}", e));
+            writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Connects to a running daemon and implements [`Transport`](crate::device::Transport) by
+/// round-tripping packets over the socket, so every command-layer function works against it
+/// exactly as it does against a real [`Device`](crate::device::Device).
+pub struct Client {
+    // A single daemon connection serves one client at a time; sending mutates the socket, so
+    // it's behind a Mutex to keep `send(&self, ...)` matching the Transport signature.
+    stream: std::sync::Mutex<BufReader<UnixStream>>,
+}
+
+impl Client {
+    pub fn connect(socket_path: &std::path::Path) -> Result<Self> {
+        let stream = UnixStream::connect(socket_path)
+            .with_context(|| format!("Failed to connect to {}", socket_path.display()))?;
+        Ok(Self {
+            stream: std::sync::Mutex::new(BufReader::new(stream)),
+        })
+    }
+}
+
+impl crate::device::Transport for Client {
+    fn send(&self, report: Packet) -> Result<Packet> {
+        let mut reader = self.stream.lock().unwrap();
+
+        writeln!(reader.get_mut(), "{}", serde_json::to_string(&report)?)?;
+
+        let line = read_line(&mut reader)?.context("Daemon closed the connection")?;
+        let response: std::result::Result<Packet, String> =
+            serde_json::from_str(line.trim_end())?;
+        response.map_err(anyhow::Error::msg)
+    }
+}
@@ -0,0 +1,60 @@
+//! Streaming telemetry for `razer-ctl ... monitor`: the same getters [`crate::status`] uses for
+//! a one-shot snapshot, taken repeatedly on an interval instead.
+
+use crate::command;
+use crate::device::Transport;
+use crate::fancurve;
+use crate::types::{BatteryCare, CpuBoost, FanMode, FanZone, GpuBoost, PerfMode};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Sample {
+    pub timestamp_ms: u128,
+    pub perf_mode: Option<PerfMode>,
+    pub cpu_boost: Option<CpuBoost>,
+    pub gpu_boost: Option<GpuBoost>,
+    pub fan_mode: Option<FanMode>,
+    pub fan_rpm_zone1: Option<u16>,
+    pub fan_rpm_zone2: Option<u16>,
+    pub battery_care: Option<BatteryCare>,
+    pub cpu_temp_celsius: Option<f32>,
+}
+
+impl std::fmt::Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:>8}ms] perf={:?} cpu_boost={:?} gpu_boost={:?} fan={:?} rpm1={:?} rpm2={:?} \
+             battery_care={:?} temp={:?}",
+            self.timestamp_ms,
+            self.perf_mode,
+            self.cpu_boost,
+            self.gpu_boost,
+            self.fan_mode,
+            self.fan_rpm_zone1,
+            self.fan_rpm_zone2,
+            self.battery_care,
+            self.cpu_temp_celsius
+        )
+    }
+}
+
+/// Takes one telemetry sample, timestamped relative to `since`. Every reading is best-effort: a
+/// getter a device doesn't support, or one that fails transiently, is simply omitted rather than
+/// aborting the whole sample.
+pub fn sample(device: &impl Transport, since: &std::time::Instant) -> Sample {
+    let perf_mode = command::get_perf_mode(device).ok();
+
+    Sample {
+        timestamp_ms: since.elapsed().as_millis(),
+        perf_mode: perf_mode.map(|(perf_mode, _)| perf_mode),
+        cpu_boost: command::get_cpu_boost(device).ok(),
+        gpu_boost: command::get_gpu_boost(device).ok(),
+        fan_mode: perf_mode.map(|(_, fan_mode)| fan_mode),
+        fan_rpm_zone1: command::get_fan_rpm(device, FanZone::Zone1).ok(),
+        fan_rpm_zone2: command::get_fan_rpm(device, FanZone::Zone2).ok(),
+        battery_care: command::get_battery_care(device).ok(),
+        cpu_temp_celsius: fancurve::read_cpu_temperature().ok(),
+    }
+}
@@ -54,6 +54,21 @@ impl Packet {
         &self.args
     }
 
+    pub fn command(&self) -> u16 {
+        ((self.command_class as u16) << 8) | self.command_id as u16
+    }
+
+    /// Builds a canned response to `self`, copying over the fields `ensure_matches_report`
+    /// checks so the reply looks like it actually came from the device. Intended for tests
+    /// that fake a [`crate::device::Transport`] against recorded packets.
+    pub fn reply(&self, args: &[u8]) -> Packet {
+        let mut response = Packet::new(self.command(), args);
+        response.id = self.id;
+        response.remaining_packets = self.remaining_packets;
+        response.status = CommandStatus::Successful as u8;
+        response
+    }
+
     pub fn ensure_matches_report(self, report: &Packet) -> Result<Self> {
         ensure!(
             (report.command_class, report.command_id, report.id)
@@ -0,0 +1,39 @@
+//! AC/battery power-source detection, for services that change device behavior based on whether
+//! the laptop is plugged in.
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+/// Reads the first `Mains`-type supply under `/sys/class/power_supply` and reports whether it's
+/// online.
+pub fn read_power_source() -> Result<PowerSource> {
+    #[cfg(target_os = "linux")]
+    {
+        for entry in std::fs::read_dir("/sys/class/power_supply")
+            .context("Failed to read /sys/class/power_supply")?
+            .flatten()
+        {
+            let path = entry.path();
+            let supply_type = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+            if supply_type.trim() != "Mains" {
+                continue;
+            }
+
+            let online = std::fs::read_to_string(path.join("online"))
+                .with_context(|| format!("Failed to read {}/online", path.display()))?;
+            return Ok(if online.trim() == "1" {
+                PowerSource::Ac
+            } else {
+                PowerSource::Battery
+            });
+        }
+        anyhow::bail!("No Mains power supply found under /sys/class/power_supply")
+    }
+    #[cfg(not(target_os = "linux"))]
+    anyhow::bail!("Power source detection is not implemented for this platform")
+}
@@ -0,0 +1,178 @@
+use crate::command;
+use crate::device::Transport;
+use crate::types::{
+    BatteryCare, CpuBoost, FanMode, FanZone, GpuBoost, LightsAlwaysOn, LogoMode, MaxFanSpeedMode,
+    PerfMode,
+};
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// Default location of the `[profiles.<name>]` TOML file consulted by `razer-ctl ... apply`,
+/// following the XDG base directory spec.
+pub fn default_config_path() -> std::path::PathBuf {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".config")))
+        .unwrap_or_else(|| std::path::PathBuf::from("."));
+    config_home.join("razer-ctl").join("config.toml")
+}
+
+/// A fully or partially specified device state, meant to be loaded from a TOML file as one
+/// entry of a `[profiles.<name>]` table. Every field is optional: a field left unset is simply
+/// not touched by [`apply`], so a profile only needs to describe the settings it cares about.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub perf_mode: Option<PerfMode>,
+    pub cpu_boost: Option<CpuBoost>,
+    pub gpu_boost: Option<GpuBoost>,
+    pub max_fan_speed_mode: Option<MaxFanSpeedMode>,
+    pub fan_mode: Option<FanMode>,
+    pub fan_rpm: Option<u16>,
+    pub keyboard_brightness: Option<u8>,
+    pub logo_mode: Option<LogoMode>,
+    pub lights_always_on: Option<LightsAlwaysOn>,
+    pub battery_care: Option<BatteryCare>,
+}
+
+/// Top-level shape of the TOML file consulted by `razer-ctl ... apply`: one named [`Profile`]
+/// per `[profiles.<name>]` table, so a single file can hold e.g. both `gaming` and `quiet`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    pub profiles: std::collections::HashMap<String, Profile>,
+}
+
+/// Apply `profile` to `device`, sequencing the individual setters so the mode-precondition
+/// `ensure!`s in [`crate::command`] are already satisfied by the time each one runs: switch to
+/// [`PerfMode::Custom`] before CPU/GPU boost, and to [`PerfMode::Balanced`] + [`FanMode::Manual`]
+/// before a fan RPM.
+pub fn apply(device: &impl Transport, profile: &Profile) -> Result<()> {
+    let needs_custom_mode = profile.cpu_boost.is_some()
+        || profile.gpu_boost.is_some()
+        || profile.max_fan_speed_mode.is_some();
+    let needs_manual_fan =
+        profile.fan_rpm.is_some() || profile.fan_mode == Some(FanMode::Manual);
+    ensure!(
+        !(needs_custom_mode && needs_manual_fan),
+        "custom boost and manual fan rpm cannot both be set in one profile"
+    );
+
+    if needs_custom_mode {
+        command::set_perf_mode(device, PerfMode::Custom)?;
+    } else if let Some(perf_mode) = profile.perf_mode {
+        command::set_perf_mode(device, perf_mode)?;
+    }
+
+    if let Some(cpu_boost) = profile.cpu_boost {
+        command::set_cpu_boost(device, cpu_boost)?;
+    }
+    if let Some(gpu_boost) = profile.gpu_boost {
+        command::set_gpu_boost(device, gpu_boost)?;
+    }
+    if let Some(max_fan_speed_mode) = profile.max_fan_speed_mode {
+        command::set_max_fan_speed_mode(device, max_fan_speed_mode)?;
+    }
+
+    if needs_manual_fan {
+        command::set_perf_mode(device, PerfMode::Balanced)?;
+        command::set_fan_mode(device, FanMode::Manual)?;
+    } else if profile.fan_mode == Some(FanMode::Auto)
+        && !needs_custom_mode
+        && profile.perf_mode.is_none()
+    {
+        // Any `set_perf_mode` above already left the fan on auto; only fall back to Balanced
+        // when the profile names no performance mode of its own.
+        command::set_perf_mode(device, PerfMode::Balanced)?;
+        command::set_fan_mode(device, FanMode::Auto)?;
+    }
+    if let Some(rpm) = profile.fan_rpm {
+        command::set_fan_rpm(device, rpm)?;
+    }
+
+    if let Some(brightness) = profile.keyboard_brightness {
+        command::set_keyboard_brightness(device, brightness)?;
+    }
+    if let Some(logo_mode) = profile.logo_mode {
+        command::set_logo_mode(device, logo_mode)?;
+    }
+    if let Some(lights_always_on) = profile.lights_always_on {
+        command::set_lights_always_on(device, lights_always_on)?;
+    }
+    if let Some(battery_care) = profile.battery_care {
+        command::set_battery_care(device, battery_care)?;
+    }
+
+    Ok(())
+}
+
+/// Snapshot the device's current state into a [`Profile`] that can be serialized back to TOML,
+/// e.g. so a user can save it as a named preset and restore it later via [`apply`].
+pub fn dump(device: &impl Transport) -> Result<Profile> {
+    let (perf_mode, fan_mode) = command::get_perf_mode(device)?;
+
+    let (cpu_boost, gpu_boost, max_fan_speed_mode) = if perf_mode == PerfMode::Custom {
+        (
+            Some(command::get_cpu_boost(device)?),
+            Some(command::get_gpu_boost(device)?),
+            command::get_max_fan_speed_mode(device).ok(),
+        )
+    } else {
+        (None, None, None)
+    };
+
+    let fan_rpm = match fan_mode {
+        FanMode::Manual => Some(command::get_fan_rpm(device, FanZone::Zone1)?),
+        FanMode::Auto => None,
+    };
+
+    Ok(Profile {
+        perf_mode: Some(perf_mode),
+        cpu_boost,
+        gpu_boost,
+        max_fan_speed_mode,
+        fan_mode: Some(fan_mode),
+        fan_rpm,
+        keyboard_brightness: Some(command::get_keyboard_brightness(device)?),
+        logo_mode: Some(command::get_logo_mode(device)?),
+        lights_always_on: Some(command::get_lights_always_on(device)?),
+        battery_care: Some(command::get_battery_care(device)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::Packet;
+
+    /// Never expects a call: [`apply`]'s mutual-exclusion `ensure!` must reject the profile
+    /// before any command reaches the device.
+    struct UnreachableTransport;
+
+    impl Transport for UnreachableTransport {
+        fn send(&self, _report: Packet) -> Result<Packet> {
+            panic!("device should not be reached once the mutual-exclusion check rejects the profile");
+        }
+    }
+
+    #[test]
+    fn apply_rejects_custom_boost_with_manual_fan_rpm() {
+        let profile = Profile {
+            cpu_boost: Some(CpuBoost::Boost),
+            fan_rpm: Some(3000),
+            ..Default::default()
+        };
+
+        assert!(apply(&UnreachableTransport, &profile).is_err());
+    }
+
+    #[test]
+    fn apply_rejects_max_fan_speed_mode_with_manual_fan_mode() {
+        let profile = Profile {
+            max_fan_speed_mode: Some(MaxFanSpeedMode::Enable),
+            fan_mode: Some(FanMode::Manual),
+            ..Default::default()
+        };
+
+        assert!(apply(&UnreachableTransport, &profile).is_err());
+    }
+}
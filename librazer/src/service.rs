@@ -0,0 +1,61 @@
+//! Edge-triggered AC/battery service: applies one of two named [`Profile`]s, but only on an
+//! actual power-source transition, so `command::set_perf_mode` and friends aren't re-issued
+//! every tick. A profile that leaves `battery_care` unset (the common case) is a no-op for
+//! [`crate::command::set_battery_care`], so charging behavior stays whatever the user configured
+//! independent of AC/battery transitions.
+
+use crate::device::Transport;
+use crate::power::{self, PowerSource};
+use crate::profile::{self, Profile};
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Runs until `running` is cleared. The first reading always counts as a transition, so the
+/// correct profile is applied once on startup rather than waiting for the next power-source
+/// change.
+pub fn watch(
+    device: &impl Transport,
+    ac_profile: &Profile,
+    battery_profile: &Profile,
+    poll_interval: Duration,
+    running: &AtomicBool,
+) -> Result<()> {
+    let mut last_source: Option<PowerSource> = None;
+
+    while running.load(Ordering::SeqCst) {
+        let source = power::read_power_source()?;
+        if Some(source) != last_source {
+            let profile = match source {
+                PowerSource::Ac => ac_profile,
+                PowerSource::Battery => battery_profile,
+            };
+            println!("Power source changed to {:?}, applying profile", source);
+            profile::apply(device, profile)?;
+            last_source = Some(source);
+        }
+        std::thread::sleep(poll_interval);
+    }
+
+    Ok(())
+}
+
+/// A systemd unit that runs `exec_start` (expected to be `razer-ctl ... service watch ...`)
+/// unattended. Printed to stdout rather than installed, matching how `profile dump` prints TOML
+/// for the user to redirect/edit themselves.
+pub fn systemd_unit(exec_start: &str) -> String {
+    format!(
+        "[Unit]\n\
+         Description=razer-ctl AC/battery-aware performance service\n\
+         After=multi-user.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exec_start}\n\
+         Restart=on-failure\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n"
+    )
+}
@@ -0,0 +1,97 @@
+use crate::command;
+use crate::descriptor::Descriptor;
+use crate::device::Transport;
+use crate::types::{
+    BatteryCare, CpuBoost, FanMode, FanZone, GpuBoost, LightsAlwaysOn, LogoMode,
+    MaxFanSpeedMode, PerfMode,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// A structured snapshot of a device's current state, for the `--json` status output. A field is
+/// `None` because the model's [`Descriptor::features`] doesn't list the feature it belongs to,
+/// because the device isn't currently in the mode that field applies to, or because reading it
+/// failed (only a failed performance/fan mode read fails the whole snapshot).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeviceStatus {
+    pub perf_mode: Option<PerfMode>,
+    pub cpu_boost: Option<CpuBoost>,
+    pub gpu_boost: Option<GpuBoost>,
+    pub fan_mode: Option<FanMode>,
+    pub fan_rpm: Option<u16>,
+    pub max_fan_speed_mode: Option<MaxFanSpeedMode>,
+    pub keyboard_brightness: Option<u8>,
+    pub logo_mode: Option<LogoMode>,
+    pub lights_always_on: Option<LightsAlwaysOn>,
+    pub battery_care: Option<BatteryCare>,
+}
+
+fn has_feature(info: &Descriptor, feature: &str) -> bool {
+    info.features.iter().any(|&f| f == feature)
+}
+
+/// Reads every setting `info` advertises support for via the existing `get_*` commands.
+pub fn status(device: &impl Transport, info: &Descriptor) -> Result<DeviceStatus> {
+    let mut status = DeviceStatus::default();
+
+    if has_feature(info, "perf") || has_feature(info, "fan") {
+        let (perf_mode, fan_mode) = command::get_perf_mode(device)?;
+
+        if has_feature(info, "perf") {
+            status.perf_mode = Some(perf_mode);
+            if perf_mode == PerfMode::Custom {
+                status.cpu_boost = command::get_cpu_boost(device).ok();
+                status.gpu_boost = command::get_gpu_boost(device).ok();
+            }
+        }
+
+        if has_feature(info, "fan") {
+            if perf_mode == PerfMode::Balanced {
+                status.fan_mode = Some(fan_mode);
+                if fan_mode == FanMode::Manual {
+                    status.fan_rpm = command::get_fan_rpm(device, FanZone::Zone1).ok();
+                }
+            }
+            if perf_mode == PerfMode::Custom {
+                status.max_fan_speed_mode = command::get_max_fan_speed_mode(device).ok();
+            }
+        }
+    }
+
+    if has_feature(info, "kbd-backlight") {
+        status.keyboard_brightness = command::get_keyboard_brightness(device).ok();
+    }
+    if has_feature(info, "lid-logo") {
+        status.logo_mode = command::get_logo_mode(device).ok();
+    }
+    if has_feature(info, "lights-always-on") {
+        status.lights_always_on = command::get_lights_always_on(device).ok();
+    }
+    if has_feature(info, "battery-care") {
+        status.battery_care = command::get_battery_care(device).ok();
+    }
+
+    Ok(status)
+}
+
+/// Whether a feature in [`Descriptor::features`] exposes a getter, a setter, or both. Every
+/// feature currently implemented in [`crate::command`] has both, but this gives GUIs/scripts a
+/// single place to discover capabilities instead of hardcoding [`crate::descriptor::SUPPORTED`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Capability {
+    pub feature: &'static str,
+    pub gettable: bool,
+    pub settable: bool,
+}
+
+pub fn capabilities(info: &Descriptor) -> Vec<Capability> {
+    info.features
+        .iter()
+        .map(|&feature| Capability {
+            feature,
+            gettable: true,
+            settable: true,
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+//! Packet capture/replay, for reverse-engineering unsupported models: record what a working
+//! driver (e.g. Synapse on Windows) sends for a given action, diff it against what this crate
+//! sends, then add the missing command to [`crate::command`] or a new entry to
+//! [`crate::descriptor::SUPPORTED`].
+
+use crate::device::Transport;
+use crate::packet::Packet;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub timestamp_ms: u128,
+    pub direction: Direction,
+    pub command: u16,
+    pub args: Vec<u8>,
+}
+
+impl TraceEntry {
+    fn new(direction: Direction, packet: &Packet, since: &std::time::Instant) -> Self {
+        Self {
+            timestamp_ms: since.elapsed().as_millis(),
+            direction,
+            command: packet.command(),
+            args: packet.get_args().to_vec(),
+        }
+    }
+}
+
+/// Appends every sent/received packet to a JSONL file, one [`TraceEntry`] per line.
+pub struct Capture {
+    file: std::sync::Mutex<std::fs::File>,
+    start: std::time::Instant,
+}
+
+impl Capture {
+    pub fn open(path: &std::path::Path) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open capture file {}", path.display()))?;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+            start: std::time::Instant::now(),
+        })
+    }
+
+    pub fn record(&self, direction: Direction, packet: &Packet) -> Result<()> {
+        let entry = TraceEntry::new(direction, packet, &self.start);
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Re-issues every captured request (recorded responses are skipped) from a JSONL trace file
+/// produced by [`Capture`], printing each response as it comes back.
+pub fn replay(device: &impl Transport, path: &std::path::Path) -> Result<()> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open trace file {}", path.display()))?;
+
+    for line in BufReader::new(file).lines() {
+        let entry: TraceEntry = serde_json::from_str(&line?)?;
+        if entry.direction != Direction::Sent {
+            continue;
+        }
+
+        let response = device.send(Packet::new(entry.command, &entry.args))?;
+        println!(
+            "{:04x} {:02x?} -> {:02x?}",
+            entry.command,
+            entry.args,
+            response.get_args()
+        );
+    }
+    Ok(())
+}
@@ -15,7 +15,7 @@ pub enum FanZone {
     Zone2 = 0x02,
 }
 
-#[derive(EnumIter, Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[derive(EnumIter, Clone, Copy, Debug, PartialEq, ValueEnum, Serialize, Deserialize)]
 pub enum PerfMode {
     Balanced = 0,
     Silent = 5,
@@ -28,7 +28,7 @@ pub enum MaxFanSpeedMode {
     Disable = 0,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum FanMode {
     Auto = 0,
     Manual = 1,
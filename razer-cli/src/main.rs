@@ -1,6 +1,7 @@
 use librazer::command;
 use librazer::device;
 use librazer::feature;
+use librazer::status;
 use librazer::types::{
     BatteryCare, CpuBoost, FanMode, FanZone, GpuBoost, LightsAlwaysOn, LogoMode, MaxFanSpeedMode,
     PerfMode,
@@ -9,7 +10,50 @@ use librazer::types::{
 use librazer::feature::Feature;
 
 use anyhow::Result;
-use clap::{arg, Command};
+use clap::{arg, Args, Command, FromArgMatches};
+
+// The per-feature subcommand surface (`Cli::cmd`/`Cli::handle` below) is necessarily built with
+// the `clap::Command` builder API: which features exist is only known once `Device::detect`
+// returns a live `Descriptor`, so there's no static enum a `#[derive(Subcommand)]` could name.
+// The `daemon` and `replay` arguments, `manual`'s device selector and the global `--capture` flag
+// use `#[derive(Args)]` structs. The other fixed subcommands (`info`, `profile`, `monitor`,
+// `service`, and `fan`'s `curve`/`pid`) are built with the builder API as well and read through
+// `ArgMatches::get_one` string lookups.
+
+/// Arguments for the `daemon` subcommand.
+#[derive(Args, Debug)]
+struct DaemonArgs {
+    /// Unix socket path to listen on
+    #[arg(long, value_name = "PATH")]
+    socket: Option<std::path::PathBuf>,
+}
+
+/// Arguments for the `replay` subcommand.
+#[derive(Args, Debug)]
+struct ReplayArgs {
+    /// Path to a JSONL trace file produced with --capture
+    file: std::path::PathBuf,
+}
+
+/// Arguments for `manual`'s device selector (the subcommand surface below it is dynamic, see
+/// the module-level note above).
+#[derive(Args, Debug)]
+struct ManualDeviceArgs {
+    /// PID of the Razer device to use
+    #[arg(short, long, value_name = "PID", value_parser = clap_num::maybe_hex::<u16>)]
+    pid: u16,
+    /// Model number prefix to report, e.g. RZ09-0483
+    #[arg(short, long, value_name = "PREFIX")]
+    model: Option<String>,
+}
+
+/// Global flags available on every invocation.
+#[derive(Args, Debug)]
+struct GlobalArgs {
+    /// Append every sent/received packet to PATH as JSONL, for reverse-engineering
+    #[arg(long, global = true, value_name = "PATH")]
+    capture: Option<std::path::PathBuf>,
+}
 
 trait Cli: feature::Feature {
     fn cmd(&self) -> Option<Command> {
@@ -112,6 +156,29 @@ impl Cli for CustomCommand {
     }
 }
 
+fn parse_curve_point(s: &str) -> Result<librazer::fancurve::CurvePoint, String> {
+    let (temp, rpm) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected TEMP:RPM, e.g. 60:3000, got {:?}", s))?;
+    Ok(librazer::fancurve::CurvePoint {
+        temp_celsius: temp
+            .parse()
+            .map_err(|e| format!("invalid temperature {:?}: {}", temp, e))?,
+        rpm: rpm
+            .parse()
+            .map_err(|e| format!("invalid rpm {:?}: {}", rpm, e))?,
+    })
+}
+
+/// Installs a SIGINT handler that clears the returned flag, so a daemon loop can restore
+/// `FanMode::Auto` on Ctrl-C instead of leaving the fan pinned at its last commanded RPM.
+fn install_ctrlc_flag() -> Result<std::sync::Arc<std::sync::atomic::AtomicBool>> {
+    let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let handler_running = running.clone();
+    ctrlc::set_handler(move || handler_running.store(false, std::sync::atomic::Ordering::SeqCst))?;
+    Ok(running)
+}
+
 impl Cli for feature::Fan {
     fn cmd(&self) -> Option<Command> {
         Some(
@@ -121,6 +188,45 @@ impl Cli for feature::Fan {
                 .subcommand(clap::Command::new("manual").about("Set fan mode to manual"))
                 .subcommand(impl_unary_cmd_cli!{{clap::value_parser!(u16).range(2000..=5000)}, "rpm", "RPM", "Set fan rpm", "Fan RPM in range [2000, 5000]"})
                 .subcommand(impl_unary_cmd_cli!{{clap::value_parser!(MaxFanSpeedMode)}, "max", "MAX", "Control Max Fan Speed Mode", "Max Fan Speed Mode"})
+                .subcommand(
+                    clap::Command::new("curve")
+                        .about("Run a temperature-driven fan curve daemon until interrupted")
+                        .arg(
+                            arg!(--point <TEMP_RPM> "Control point TEMP:RPM, e.g. 60:3000 (repeatable)")
+                                .required(true)
+                                .action(clap::ArgAction::Append)
+                                .value_parser(parse_curve_point),
+                        )
+                        .arg(
+                            arg!(--"interval-ms" <MS> "Polling interval in milliseconds")
+                                .required(false)
+                                .value_parser(clap::value_parser!(u64))
+                                .default_value("2000"),
+                        )
+                        .arg(
+                            arg!(--"deadband-rpm" <RPM> "Minimum RPM drift before re-issuing a command")
+                                .required(false)
+                                .value_parser(clap::value_parser!(u16))
+                                .default_value("200"),
+                        )
+                        .arg(arg!(--verbose "Log every temperature -> rpm decision").required(false)),
+                )
+                .subcommand(
+                    clap::Command::new("pid")
+                        .about("Run a PID fan controller holding a target temperature, until interrupted")
+                        .arg(arg!(--kp <KP> "Proportional gain").required(true).value_parser(clap::value_parser!(f32)))
+                        .arg(arg!(--ki <KI> "Integral gain").required(true).value_parser(clap::value_parser!(f32)))
+                        .arg(arg!(--kd <KD> "Derivative gain").required(true).value_parser(clap::value_parser!(f32)))
+                        .arg(arg!(--setpoint <CELSIUS> "Target temperature").required(true).value_parser(clap::value_parser!(f32)))
+                        .arg(
+                            arg!(--"interval-ms" <MS> "Polling interval in milliseconds")
+                                .required(false)
+                                .value_parser(clap::value_parser!(u64))
+                                .default_value("2000"),
+                        )
+                        .arg(arg!(--verbose "Log every temperature -> rpm decision").required(false))
+                        .arg_required_else_help(true),
+                )
                 .arg_required_else_help(true),
         )
     }
@@ -134,6 +240,41 @@ impl Cli for feature::Fan {
                 match matches.subcommand() {
                     Some(("auto", _)) => command::set_fan_mode(device, FanMode::Auto),
                     Some(("manual", _)) => command::set_fan_mode(device, FanMode::Manual),
+                    Some(("curve", matches)) => {
+                        let mut points: Vec<_> = matches
+                            .get_many::<librazer::fancurve::CurvePoint>("point")
+                            .unwrap()
+                            .copied()
+                            .collect();
+                        points.sort_by(|a, b| a.temp_celsius.total_cmp(&b.temp_celsius));
+                        let interval = std::time::Duration::from_millis(
+                            *matches.get_one::<u64>("interval-ms").unwrap(),
+                        );
+                        let deadband = *matches.get_one::<u16>("deadband-rpm").unwrap();
+                        let verbose = matches.get_flag("verbose");
+                        let running = install_ctrlc_flag()?;
+
+                        librazer::fancurve::run_curve(
+                            device, &points, interval, deadband, verbose, &running,
+                        )
+                    }
+                    Some(("pid", matches)) => {
+                        let gains = librazer::fancurve::PidGains {
+                            kp: *matches.get_one::<f32>("kp").unwrap(),
+                            ki: *matches.get_one::<f32>("ki").unwrap(),
+                            kd: *matches.get_one::<f32>("kd").unwrap(),
+                        };
+                        let setpoint = *matches.get_one::<f32>("setpoint").unwrap();
+                        let interval = std::time::Duration::from_millis(
+                            *matches.get_one::<u64>("interval-ms").unwrap(),
+                        );
+                        let verbose = matches.get_flag("verbose");
+                        let running = install_ctrlc_flag()?;
+
+                        librazer::fancurve::run_pid(
+                            device, gains, setpoint, interval, verbose, &running,
+                        )
+                    }
                     _ => Ok(()),
                 }
             }
@@ -227,10 +368,32 @@ fn handle(
     matches: &clap::ArgMatches,
     features: &Vec<Box<dyn Cli>>,
 ) -> Result<()> {
-    if let Some(("info", _)) = matches.subcommand() {
+    if let Some(("info", submatches)) = matches.subcommand() {
+        if submatches.get_flag("capabilities") {
+            let capabilities = status::capabilities(&device.info);
+            println!("{}", serde_json::to_string_pretty(&capabilities)?);
+            return Ok(());
+        }
+        if submatches.get_flag("json") {
+            let status = status::status(device, &device.info)?;
+            println!("{}", serde_json::to_string_pretty(&status)?);
+            return Ok(());
+        }
         println!("Device: {:?}", device.info);
     }
 
+    if let Some(("profile", submatches)) = matches.subcommand() {
+        return handle_profile(device, submatches);
+    }
+
+    if let Some(("monitor", submatches)) = matches.subcommand() {
+        return handle_monitor(device, submatches);
+    }
+
+    if let Some(("service", submatches)) = matches.subcommand() {
+        return handle_service(device, submatches);
+    }
+
     for f in features {
         f.handle(device, matches)?;
     }
@@ -245,26 +408,193 @@ fn gen_cli_features(feature_list: &[&str]) -> Vec<Box<dyn Cli>> {
         .collect()
 }
 
+fn profile_cmd() -> Command {
+    clap::Command::new("profile")
+        .about("Dump or apply a full set of settings from a TOML file")
+        .subcommand(clap::Command::new("dump").about("Print the device's current state as a TOML profile"))
+        .subcommand(
+            clap::Command::new("apply")
+                .about("Apply a named [profiles.<name>] profile from a TOML file")
+                .arg(arg!(--profile <NAME> "Name of the profile to apply").required(true))
+                .arg(
+                    arg!(--config <PATH> "Path to the TOML file holding one or more [profiles.<name>] tables")
+                        .required(false),
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand_required(true)
+}
+
+fn handle_profile(device: &device::Device, matches: &clap::ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("dump", _)) => {
+            let profile = librazer::profile::dump(device)?;
+            print!("{}", toml::to_string_pretty(&profile)?);
+            Ok(())
+        }
+        Some(("apply", matches)) => {
+            let name = matches.get_one::<String>("profile").unwrap();
+            let path = matches
+                .get_one::<String>("config")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(librazer::profile::default_config_path);
+
+            let config: librazer::profile::Config =
+                toml::from_str(&std::fs::read_to_string(&path)?)?;
+            let profile = config.profiles.get(name).ok_or_else(|| {
+                anyhow::anyhow!("Profile {:?} not found in {}", name, path.display())
+            })?;
+
+            librazer::profile::apply(device, profile)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn monitor_cmd() -> Command {
+    clap::Command::new("monitor")
+        .about("Continuously print device telemetry samples, for logging or dashboards")
+        .arg(
+            arg!(--interval <MS> "Polling interval in milliseconds")
+                .required(false)
+                .value_parser(clap::value_parser!(u64))
+                .default_value("1000"),
+        )
+        .arg(
+            arg!(--count <N> "Stop after N samples (default: run until interrupted)")
+                .required(false)
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            arg!(--format <FORMAT> "Output format")
+                .required(false)
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+}
+
+fn handle_monitor(device: &device::Device, matches: &clap::ArgMatches) -> Result<()> {
+    let interval = std::time::Duration::from_millis(*matches.get_one::<u64>("interval").unwrap());
+    let count = matches.get_one::<u64>("count").copied();
+    let json = matches.get_one::<String>("format").map(|f| f.as_str()) == Some("json");
+
+    let since = std::time::Instant::now();
+    let mut samples_taken: u64 = 0;
+    loop {
+        let sample = librazer::monitor::sample(device, &since);
+        if json {
+            println!("{}", serde_json::to_string(&sample)?);
+        } else {
+            println!("{}", sample);
+        }
+
+        samples_taken += 1;
+        if count.is_some_and(|count| samples_taken >= count) {
+            break;
+        }
+        std::thread::sleep(interval);
+    }
+    Ok(())
+}
+
+fn service_cmd() -> Command {
+    clap::Command::new("service")
+        .about("Automatically switch profiles based on AC/battery power source")
+        .subcommand(
+            clap::Command::new("watch")
+                .about("Run in the foreground, applying a profile on every power source transition")
+                .arg(
+                    arg!(--config <PATH> "Path to the TOML file holding [profiles.<name>] tables")
+                        .required(false),
+                )
+                .arg(arg!(--ac <NAME> "Profile to apply when on AC power").required(true))
+                .arg(arg!(--battery <NAME> "Profile to apply when on battery power").required(true))
+                .arg(
+                    arg!(--"interval-ms" <MS> "Polling interval in milliseconds")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("5000"),
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            clap::Command::new("systemd-unit")
+                .about("Print a systemd unit that runs `service watch` unattended"),
+        )
+        .subcommand_required(true)
+}
+
+fn handle_service(device: &device::Device, matches: &clap::ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("watch", matches)) => {
+            let path = matches
+                .get_one::<String>("config")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(librazer::profile::default_config_path);
+            let config: librazer::profile::Config =
+                toml::from_str(&std::fs::read_to_string(&path)?)?;
+
+            let ac_name = matches.get_one::<String>("ac").unwrap();
+            let battery_name = matches.get_one::<String>("battery").unwrap();
+            let ac_profile = config.profiles.get(ac_name).ok_or_else(|| {
+                anyhow::anyhow!("Profile {:?} not found in {}", ac_name, path.display())
+            })?;
+            let battery_profile = config.profiles.get(battery_name).ok_or_else(|| {
+                anyhow::anyhow!("Profile {:?} not found in {}", battery_name, path.display())
+            })?;
+
+            let interval = std::time::Duration::from_millis(
+                *matches.get_one::<u64>("interval-ms").unwrap(),
+            );
+            let running = install_ctrlc_flag()?;
+
+            librazer::service::watch(device, ac_profile, battery_profile, interval, &running)
+        }
+        Some(("systemd-unit", _)) => {
+            let exec_start = format!(
+                "{} auto service watch --ac <NAME> --battery <NAME>",
+                std::env::current_exe()?.display()
+            );
+            print!("{}", librazer::service::systemd_unit(&exec_start));
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
 fn main() -> Result<()> {
-    let info_cmd = clap::Command::new("info").about("Get device info");
+    let info_cmd = clap::Command::new("info")
+        .about("Get device info")
+        .arg(arg!(--json "Print current settings as machine-readable JSON").required(false))
+        .arg(
+            arg!(--capabilities "Print supported features as machine-readable JSON, without touching the device")
+                .required(false),
+        );
+    let profile_cmd = profile_cmd();
+    let monitor_cmd = monitor_cmd();
+    let service_cmd = service_cmd();
     let auto_cmd = clap::Command::new("auto")
         .about("Automatically detect supported Razer device and enable device specific features")
         .subcommand(info_cmd.clone())
+        .subcommand(profile_cmd.clone())
+        .subcommand(monitor_cmd.clone())
+        .subcommand(service_cmd.clone())
         .subcommand_required(true);
 
-    let manual_cmd =clap::Command::new("manual").about("Manually specify PID of the Razer device and enable all features (many might not work)")
-            .arg(
-                arg!(-p --pid <PID> "PID of the Razer device to use")
-                .required(true)
-                .value_parser(clap_num::maybe_hex::<u16>)
-            )
-            .arg_required_else_help(true)
-            .subcommand(info_cmd)
-            .subcommand_required(true);
+    let manual_cmd = ManualDeviceArgs::augment_args(
+        clap::Command::new("manual")
+            .about("Manually specify PID of the Razer device and enable all features (many might not work)"),
+    )
+    .arg_required_else_help(true)
+    .subcommand(info_cmd)
+    .subcommand(profile_cmd)
+    .subcommand(monitor_cmd)
+    .subcommand(service_cmd)
+    .subcommand_required(true);
 
     // TODO: find a better way to detect auto mode in advance
     let is_auto_mode = std::env::args_os().nth(1) == Some("auto".into());
-    let device = is_auto_mode.then_some(device::Device::detect()?);
+    let mut device = is_auto_mode.then_some(device::Device::detect()?);
     let feature_list = match device {
         Some(ref device) => device.info.features,
         _ => feature::ALL_FEATURES,
@@ -273,29 +603,72 @@ fn main() -> Result<()> {
     let mut cli_features: Vec<Box<dyn Cli>> = gen_cli_features(feature_list);
     cli_features.push(Box::new(CustomCommand));
 
-    let cmd = clap::command!()
+    let cmd = GlobalArgs::augment_args(clap::command!())
         .color(clap::ColorChoice::Always)
         .subcommand_required(true)
         .subcommand(update_cmd(auto_cmd, &cli_features))
         .subcommand(update_cmd(manual_cmd, &cli_features))
-        .subcommand(clap::Command::new("enumerate").about("List discovered Razer devices"));
+        .subcommand(clap::Command::new("enumerate").about("List discovered Razer devices"))
+        .subcommand(DaemonArgs::augment_args(
+            clap::Command::new("daemon")
+                .about("Run a background daemon that keeps the device open and serializes access to it"),
+        ))
+        .subcommand(
+            ReplayArgs::augment_args(
+                clap::Command::new("replay").about("Re-issue every request recorded by --capture"),
+            )
+            .arg_required_else_help(true),
+        );
 
     let matches = cmd.get_matches();
+    let global_args = GlobalArgs::from_arg_matches(&matches)?;
+    let capture_path = global_args.capture;
+
+    if let (Some(path), Some(device)) = (&capture_path, device.as_mut()) {
+        device.enable_capture(path)?;
+    }
 
     match matches.subcommand() {
         Some(("enumerate", _)) => {
             enumerate()?;
         }
+        Some(("daemon", submatches)) => {
+            let args = DaemonArgs::from_arg_matches(submatches)?;
+            let socket_path = args.socket.unwrap_or_else(librazer::ipc::default_socket_path);
+            let mut daemon_device = device::Device::detect()?;
+            if let Some(path) = &capture_path {
+                daemon_device.enable_capture(path)?;
+            }
+            println!("Listening on {}", socket_path.display());
+            librazer::ipc::host::run(&daemon_device, &socket_path)?;
+        }
+        Some(("replay", submatches)) => {
+            let args = ReplayArgs::from_arg_matches(submatches)?;
+            let mut replay_device = device::Device::detect()?;
+            if let Some(path) = &capture_path {
+                replay_device.enable_capture(path)?;
+            }
+            librazer::trace::replay(&replay_device, &args.file)?;
+        }
         Some(("auto", submatches)) => {
             handle(&device.unwrap(), submatches, &cli_features)?;
         }
         Some(("manual", submatches)) => {
-            let device = device::Device::new(device::Descriptor {
-                model_number_prefix: "Unknown",
+            let args = ManualDeviceArgs::from_arg_matches(submatches)?;
+            // Leaked for the process lifetime so it fits Descriptor's `&'static str`.
+            let model_number_prefix = args
+                .model
+                .map(|model| &*Box::leak(model.into_boxed_str()))
+                .unwrap_or("Unknown");
+            let mut device = device::Device::new(device::Descriptor {
+                model_number_prefix,
                 name: "Unknown",
-                pid: *submatches.get_one::<u16>("pid").unwrap(),
+                pid: args.pid,
                 features: feature::ALL_FEATURES,
             })?;
+            if let Some(path) = &capture_path {
+                device.enable_capture(path)?;
+            }
             handle(&device, submatches, &cli_features)?;
         }
         Some((cmd, _)) => unimplemented!("Subcommand not implemented: {}", cmd),
@@ -0,0 +1,42 @@
+//! Laptop battery charge level and charging state, read via the Windows power API. Polled on its
+//! own (longer) interval in `main`, separate from the device-state reconciliation poll, so
+//! checking it doesn't add extra traffic on the HID channel.
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryStatus {
+    pub percent: u8,
+    /// Whether the battery is actually charging, per `BatteryFlag`'s `BATTERY_FLAG_CHARGING` bit.
+    /// Not the same as being plugged into AC: a full battery on AC reports this as `false`.
+    pub charging: bool,
+    /// Whether AC power is connected, per `ACLineStatus`. This is the signal power-source
+    /// auto-switching cares about, independent of whether the battery itself is charging.
+    pub on_ac: bool,
+}
+
+#[cfg(target_os = "windows")]
+pub fn read() -> Result<BatteryStatus> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    const BATTERY_FLAG_CHARGING: u8 = 8;
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        bail!("GetSystemPowerStatus failed");
+    }
+    if status.BatteryLifePercent == 255 {
+        bail!("No battery reported by GetSystemPowerStatus");
+    }
+
+    Ok(BatteryStatus {
+        percent: status.BatteryLifePercent,
+        charging: status.BatteryFlag & BATTERY_FLAG_CHARGING != 0,
+        on_ac: status.ACLineStatus == 1,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn read() -> Result<BatteryStatus> {
+    bail!("Battery status is only implemented on Windows")
+}
@@ -0,0 +1,26 @@
+//! Windows console window used for live log streaming, toggled from the tray menu's "Show Log
+//! Window" item. The console is allocated once on first use and then just hidden/shown on
+//! further toggles, since freeing and reallocating it would lose its scrollback.
+
+use anyhow::Result;
+
+#[cfg(target_os = "windows")]
+pub fn set_visible(visible: bool) -> Result<()> {
+    use windows_sys::Win32::System::Console::{AllocConsole, GetConsoleWindow};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_HIDE, SW_SHOW};
+
+    let mut hwnd = unsafe { GetConsoleWindow() };
+    if hwnd == 0 {
+        if unsafe { AllocConsole() } == 0 {
+            anyhow::bail!("AllocConsole failed");
+        }
+        hwnd = unsafe { GetConsoleWindow() };
+    }
+    unsafe { ShowWindow(hwnd, if visible { SW_SHOW } else { SW_HIDE }) };
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_visible(_visible: bool) -> Result<()> {
+    anyhow::bail!("Log window is only implemented on Windows")
+}
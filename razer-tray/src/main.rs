@@ -3,16 +3,24 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
+use librazer::descriptor::Descriptor;
 use librazer::types::{BatteryCare, CpuBoost, GpuBoost, LightsAlwaysOn, LogoMode, MaxFanSpeedMode};
 use librazer::{command, device};
 
+mod battery;
+mod console;
+
+use tao::event::Event;
 use tao::event_loop::{ControlFlow, EventLoopBuilder};
 use tray_icon::{
-    menu::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, PredefinedMenuItem, Submenu},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     TrayIconBuilder, TrayIconEvent,
 };
 
 const PKG_NAME: &str = env!("CARGO_PKG_NAME");
+/// Named profiles are persisted separately from the live device state (the unnamed config confy
+/// already stores), so saving/deleting a profile never touches the restore-on-restart state.
+const PROFILES_CONFIG_NAME: &str = "profiles";
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum FanSpeed {
@@ -41,32 +49,63 @@ struct DeviceState {
     battery_care: BatteryCare,
 }
 
+/// Mirrors the feature check `librazer::status` uses: not every model's [`Descriptor::features`]
+/// lists every command, so reads/writes and the menu built from them need to skip what a model
+/// doesn't support instead of erroring.
+fn has_feature(info: &Descriptor, feature: &str) -> bool {
+    info.features.iter().any(|&f| f == feature)
+}
+
 impl DeviceState {
     fn read(device: &device::Device) -> Result<Self> {
-        let perf_mode = match command::get_perf_mode(device)? {
-            (librazer::types::PerfMode::Silent, _) => PerfMode::Silent,
-            (librazer::types::PerfMode::Balanced, librazer::types::FanMode::Auto) => {
-                PerfMode::Balanced(FanSpeed::Auto)
-            }
-            (librazer::types::PerfMode::Balanced, librazer::types::FanMode::Manual) => {
-                let fan_speed = command::get_fan_rpm(device, librazer::types::FanZone::Zone1)?;
-                PerfMode::Balanced(FanSpeed::Manual(fan_speed))
-            }
-            (librazer::types::PerfMode::Custom, _) => {
-                let cpu_boost = command::get_cpu_boost(device)?;
-                let gpu_boost = command::get_gpu_boost(device)?;
-                let max_fan_speed = command::get_max_fan_speed_mode(device)?;
-                PerfMode::Custom(cpu_boost, gpu_boost, max_fan_speed)
+        let info = device.info();
+        let default = Self::default();
+
+        let perf_mode = if has_feature(info, "perf") || has_feature(info, "fan") {
+            match command::get_perf_mode(device)? {
+                (librazer::types::PerfMode::Silent, _) => PerfMode::Silent,
+                (librazer::types::PerfMode::Balanced, librazer::types::FanMode::Auto) => {
+                    PerfMode::Balanced(FanSpeed::Auto)
+                }
+                (librazer::types::PerfMode::Balanced, librazer::types::FanMode::Manual) => {
+                    let fan_speed =
+                        command::get_fan_rpm(device, librazer::types::FanZone::Zone1)?;
+                    PerfMode::Balanced(FanSpeed::Manual(fan_speed))
+                }
+                (librazer::types::PerfMode::Custom, _) => {
+                    let cpu_boost = command::get_cpu_boost(device)?;
+                    let gpu_boost = command::get_gpu_boost(device)?;
+                    let max_fan_speed = command::get_max_fan_speed_mode(device)?;
+                    PerfMode::Custom(cpu_boost, gpu_boost, max_fan_speed)
+                }
             }
+        } else {
+            default.perf_mode
         };
 
         let lights_mode = LightsMode {
-            logo_mode: command::get_logo_mode(device)?,
-            keyboard_brightness: command::get_keyboard_brightness(device)?,
-            always_on: command::get_lights_always_on(device)?,
+            logo_mode: if has_feature(info, "lid-logo") {
+                command::get_logo_mode(device)?
+            } else {
+                default.lights_mode.logo_mode
+            },
+            keyboard_brightness: if has_feature(info, "kbd-backlight") {
+                command::get_keyboard_brightness(device)?
+            } else {
+                default.lights_mode.keyboard_brightness
+            },
+            always_on: if has_feature(info, "lights-always-on") {
+                command::get_lights_always_on(device)?
+            } else {
+                default.lights_mode.always_on
+            },
         };
 
-        let battery_care = command::get_battery_care(device)?;
+        let battery_care = if has_feature(info, "battery-care") {
+            command::get_battery_care(device)?
+        } else {
+            default.battery_care
+        };
 
         Ok(Self {
             perf_mode,
@@ -76,33 +115,49 @@ impl DeviceState {
     }
 
     fn apply(&self, device: &device::Device) -> Result<()> {
-        match self.perf_mode {
-            PerfMode::Silent => command::set_perf_mode(device, librazer::types::PerfMode::Silent),
-            PerfMode::Balanced(FanSpeed::Auto) => {
-                command::set_perf_mode(device, librazer::types::PerfMode::Balanced)
-            }
-            PerfMode::Balanced(FanSpeed::Manual(rpm)) => {
-                command::set_perf_mode(device, librazer::types::PerfMode::Balanced)?;
-                command::set_fan_mode(device, librazer::types::FanMode::Manual)?;
-                command::set_fan_rpm(device, rpm)
-            }
-            PerfMode::Custom(cpu_boost, gpu_boost, max_fan_speed) => {
-                command::set_perf_mode(device, librazer::types::PerfMode::Custom)?;
-                command::set_cpu_boost(device, cpu_boost)?;
-                command::set_gpu_boost(device, gpu_boost)?;
-                command::set_max_fan_speed_mode(device, max_fan_speed)
-            }
-        }?;
+        let info = device.info();
+
+        if has_feature(info, "perf") || has_feature(info, "fan") {
+            match self.perf_mode {
+                PerfMode::Silent => {
+                    command::set_perf_mode(device, librazer::types::PerfMode::Silent)
+                }
+                PerfMode::Balanced(FanSpeed::Auto) => {
+                    command::set_perf_mode(device, librazer::types::PerfMode::Balanced)
+                }
+                PerfMode::Balanced(FanSpeed::Manual(rpm)) => {
+                    command::set_perf_mode(device, librazer::types::PerfMode::Balanced)?;
+                    command::set_fan_mode(device, librazer::types::FanMode::Manual)?;
+                    command::set_fan_rpm(device, rpm)
+                }
+                PerfMode::Custom(cpu_boost, gpu_boost, max_fan_speed) => {
+                    command::set_perf_mode(device, librazer::types::PerfMode::Custom)?;
+                    command::set_cpu_boost(device, cpu_boost)?;
+                    command::set_gpu_boost(device, gpu_boost)?;
+                    command::set_max_fan_speed_mode(device, max_fan_speed)
+                }
+            }?;
+        }
+
+        if has_feature(info, "lid-logo") {
+            match self.lights_mode.logo_mode {
+                LogoMode::Static => command::set_logo_mode(device, LogoMode::Static),
+                LogoMode::Breathing => command::set_logo_mode(device, LogoMode::Breathing),
+                LogoMode::Off => command::set_logo_mode(device, LogoMode::Off),
+            }?;
+        }
 
-        match self.lights_mode.logo_mode {
-            LogoMode::Static => command::set_logo_mode(device, LogoMode::Static),
-            LogoMode::Breathing => command::set_logo_mode(device, LogoMode::Breathing),
-            LogoMode::Off => command::set_logo_mode(device, LogoMode::Off),
-        }?;
+        if has_feature(info, "kbd-backlight") {
+            command::set_keyboard_brightness(device, self.lights_mode.keyboard_brightness)?;
+        }
+        if has_feature(info, "lights-always-on") {
+            command::set_lights_always_on(device, self.lights_mode.always_on)?;
+        }
+        if has_feature(info, "battery-care") {
+            command::set_battery_care(device, self.battery_care)?;
+        }
 
-        command::set_keyboard_brightness(device, self.lights_mode.keyboard_brightness)?;
-        command::set_lights_always_on(device, self.lights_mode.always_on)?;
-        command::set_battery_care(device, self.battery_care)
+        Ok(())
     }
 
     fn perf_delta(
@@ -144,6 +199,17 @@ impl Default for DeviceState {
     }
 }
 
+/// Named, user-saved device-state snapshots the tray menu lets you switch between, independent of
+/// the live state the app always restores on restart.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct Profiles {
+    profiles: std::collections::HashMap<String, DeviceState>,
+    active: Option<String>,
+    /// Profiles auto-applied on an AC-plugged/on-battery transition, bound from the tray menu.
+    on_ac: Option<String>,
+    on_battery: Option<String>,
+}
+
 trait DeviceStateDelta<T> {
     fn delta(&self, property: T) -> Self;
 }
@@ -170,254 +236,436 @@ struct ProgramState {
     device_state: DeviceState,
     event_handlers: std::collections::HashMap<String, DeviceState>,
     menu: Menu,
+    battery: Option<battery::BatteryStatus>,
+    profiles: Profiles,
+    log_window_visible: bool,
+    descriptor: Descriptor,
 }
 
 impl ProgramState {
-    fn new(device_state: DeviceState) -> Result<Self> {
-        let (menu, event_handlers) = Self::create_menu_and_handlers(&device_state)?;
+    fn new(
+        device_state: DeviceState,
+        profiles: Profiles,
+        log_window_visible: bool,
+        descriptor: Descriptor,
+    ) -> Result<Self> {
+        let (menu, event_handlers) = Self::create_menu_and_handlers(
+            &device_state,
+            &profiles,
+            log_window_visible,
+            &descriptor,
+        )?;
         Ok(Self {
             device_state,
             event_handlers,
             menu,
+            battery: None,
+            profiles,
+            log_window_visible,
+            descriptor,
         })
     }
 
+    /// Swaps in a freshly read battery status without touching the menu or device state, so the
+    /// periodic battery poll in `main` can refresh the tray icon/tooltip without re-applying
+    /// device state or persisting config.
+    fn with_battery(self, battery: Option<battery::BatteryStatus>) -> Self {
+        Self { battery, ..self }
+    }
+
+    /// Saves the live device state as a new named profile and marks it active. Returns the
+    /// updated `Profiles`, already persisted; the caller still needs to rebuild the menu from it
+    /// (via `update`), same as any other state change.
+    fn save_current_profile(&self) -> Result<Profiles> {
+        let mut profiles = self.profiles.clone();
+        let mut n = profiles.profiles.len() + 1;
+        let mut name = format!("Profile {n}");
+        while profiles.profiles.contains_key(&name) {
+            n += 1;
+            name = format!("Profile {n}");
+        }
+        profiles.profiles.insert(name.clone(), self.device_state);
+        profiles.active = Some(name);
+        confy::store(PKG_NAME, Some(PROFILES_CONFIG_NAME), &profiles)?;
+        Ok(profiles)
+    }
+
+    /// Removes a named profile, persisting the result.
+    fn delete_profile(&self, name: &str) -> Result<Profiles> {
+        let mut profiles = self.profiles.clone();
+        profiles.profiles.remove(name);
+        if profiles.active.as_deref() == Some(name) {
+            profiles.active = None;
+        }
+        confy::store(PKG_NAME, Some(PROFILES_CONFIG_NAME), &profiles)?;
+        Ok(profiles)
+    }
+
+    /// Looks up a named profile's saved device state and marks it active, persisting the result.
+    fn activate_profile(&self, name: &str) -> Result<(DeviceState, Profiles)> {
+        let device_state = *self
+            .profiles
+            .profiles
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("No such profile: {}", name))?;
+        let mut profiles = self.profiles.clone();
+        profiles.active = Some(name.to_string());
+        confy::store(PKG_NAME, Some(PROFILES_CONFIG_NAME), &profiles)?;
+        Ok((device_state, profiles))
+    }
+
+    /// Toggles `name` as the profile auto-applied when the laptop is plugged into AC; clicking
+    /// the currently-bound profile clears the binding.
+    fn bind_on_ac(&self, name: &str) -> Result<Profiles> {
+        let mut profiles = self.profiles.clone();
+        profiles.on_ac = (profiles.on_ac.as_deref() != Some(name)).then(|| name.to_string());
+        confy::store(PKG_NAME, Some(PROFILES_CONFIG_NAME), &profiles)?;
+        Ok(profiles)
+    }
+
+    /// Same as `bind_on_ac`, for running on battery.
+    fn bind_on_battery(&self, name: &str) -> Result<Profiles> {
+        let mut profiles = self.profiles.clone();
+        profiles.on_battery = (profiles.on_battery.as_deref() != Some(name)).then(|| name.to_string());
+        confy::store(PKG_NAME, Some(PROFILES_CONFIG_NAME), &profiles)?;
+        Ok(profiles)
+    }
+
     fn create_menu_and_handlers(
         dstate: &DeviceState,
+        profiles: &Profiles,
+        log_window_visible: bool,
+        descriptor: &Descriptor,
     ) -> Result<(Menu, std::collections::HashMap<String, DeviceState>)> {
         let mut event_handlers = std::collections::HashMap::new();
         let menu = Menu::new();
         // header
 
-        // perf
-        let perf_modes = Submenu::new("Performance", true);
-        // silent
-        perf_modes.append(&CheckMenuItem::with_id(
-            format!("{:?}", PerfMode::Silent),
-            "Silent",
-            dstate.perf_mode != PerfMode::Silent,
-            dstate.perf_mode == PerfMode::Silent,
-            None,
-        ))?;
-        event_handlers.insert(
-            format!("{:?}", PerfMode::Silent),
-            DeviceState {
-                perf_mode: PerfMode::Silent,
-                ..*dstate
-            },
-        );
-        // balanced
-        let fan_speeds: Vec<CheckMenuItem> = [CheckMenuItem::with_id(
-            "fan_speed:auto",
-            "Fan: Auto",
-            dstate.perf_mode != PerfMode::Balanced(FanSpeed::Auto),
-            dstate.perf_mode == PerfMode::Balanced(FanSpeed::Auto),
-            None,
-        )]
-        .into_iter()
-        .chain((2000..=5000).step_by(500).map(|rpm| {
-            let event_id = format!("fan_speed:{}", rpm);
+        // perf (not every model exposes perf modes or fan control at all)
+        if has_feature(descriptor, "perf") || has_feature(descriptor, "fan") {
+            let perf_modes = Submenu::new("Performance", true);
+            // silent
+            perf_modes.append(&CheckMenuItem::with_id(
+                format!("{:?}", PerfMode::Silent),
+                "Silent",
+                dstate.perf_mode != PerfMode::Silent,
+                dstate.perf_mode == PerfMode::Silent,
+                None,
+            ))?;
             event_handlers.insert(
-                event_id.clone(),
+                format!("{:?}", PerfMode::Silent),
                 DeviceState {
-                    perf_mode: PerfMode::Balanced(FanSpeed::Manual(rpm)),
+                    perf_mode: PerfMode::Silent,
                     ..*dstate
                 },
             );
-            CheckMenuItem::with_id(
-                event_id,
-                format!("Fan: {} RPM", rpm),
-                dstate.perf_mode != PerfMode::Balanced(FanSpeed::Manual(rpm)),
-                dstate.perf_mode == PerfMode::Balanced(FanSpeed::Manual(rpm)),
+            // balanced
+            let fan_speeds: Vec<CheckMenuItem> = [CheckMenuItem::with_id(
+                "fan_speed:auto",
+                "Fan: Auto",
+                dstate.perf_mode != PerfMode::Balanced(FanSpeed::Auto),
+                dstate.perf_mode == PerfMode::Balanced(FanSpeed::Auto),
                 None,
-            )
-        }))
-        .collect();
-        event_handlers.insert(
-            "fan_speed:auto".to_string(),
-            DeviceState {
-                perf_mode: PerfMode::Balanced(FanSpeed::Auto),
-                ..*dstate
-            },
-        );
-
-        perf_modes.append(&Submenu::with_items(
-            "Balanced",
-            true,
-            &fan_speeds
-                .iter()
-                .map(|i| i as &dyn IsMenuItem)
-                .collect::<Vec<_>>(),
-        )?)?;
-
-        // custom
-        let cpu_boosts: Vec<CheckMenuItem> = CpuBoost::iter()
-            .map(|boost| {
-                let event_id = format!("cpu_boost:{:?}", boost);
-                event_handlers.insert(event_id.clone(), dstate.delta(boost));
-                let checked = matches!(dstate.perf_mode, PerfMode::Custom(b, _, _) if b == boost);
-                CheckMenuItem::with_id(event_id, format!("{:?}", boost), !checked, checked, None)
-            })
-            .collect();
-
-        let gpu_boosts: Vec<CheckMenuItem> = GpuBoost::iter()
-            .map(|boost| {
-                let event_id = format!("gpu_boost:{:?}", boost);
-                event_handlers.insert(event_id.clone(), dstate.delta(boost));
-                let checked = matches!(dstate.perf_mode, PerfMode::Custom(_, b, _) if b == boost);
-                CheckMenuItem::with_id(event_id, format!("{:?}", boost), !checked, checked, None)
-            })
-            .collect();
-
-        let max_fan_speed_mode = &[CheckMenuItem::with_id(
-            "max_fan_speed_mode",
-            "Max Fan Speed",
-            true,
-            matches!(
-                dstate.perf_mode,
-                PerfMode::Custom(_, _, MaxFanSpeedMode::Enable)
-            ),
-            None,
-        )];
-        event_handlers.insert(
-            "max_fan_speed_mode".to_string(),
-            match dstate.perf_mode {
-                PerfMode::Custom(_, _, MaxFanSpeedMode::Enable) => {
-                    dstate.delta(MaxFanSpeedMode::Disable)
-                }
-                _ => dstate.delta(MaxFanSpeedMode::Enable),
-            },
-        );
-
-        let separator = PredefinedMenuItem::separator();
-
-        perf_modes.append(&Submenu::with_items(
-            "Custom",
-            true,
-            &cpu_boosts
-                .iter()
-                .map(|i| i as &dyn IsMenuItem)
-                .chain([&separator as &dyn IsMenuItem])
-                .chain(gpu_boosts.iter().map(|i| i as &dyn IsMenuItem))
-                .chain([&separator as &dyn IsMenuItem])
-                .chain(max_fan_speed_mode.iter().map(|i| i as &dyn IsMenuItem))
-                .collect::<Vec<_>>(),
-        )?)?;
-
-        menu.append(&perf_modes)?;
-
-        // logo
-        menu.append(&PredefinedMenuItem::separator())?;
-        let modes = LogoMode::iter()
-            .map(|mode| {
-                let event_id = format!("logo_mode:{:?}", mode);
+            )]
+            .into_iter()
+            .chain((2000..=5000).step_by(500).map(|rpm| {
+                let event_id = format!("fan_speed:{}", rpm);
                 event_handlers.insert(
                     event_id.clone(),
                     DeviceState {
-                        lights_mode: LightsMode {
-                            logo_mode: mode,
-                            ..dstate.lights_mode
-                        },
+                        perf_mode: PerfMode::Balanced(FanSpeed::Manual(rpm)),
                         ..*dstate
                     },
                 );
                 CheckMenuItem::with_id(
                     event_id,
-                    format!("{:?}", mode),
-                    dstate.lights_mode.logo_mode != mode,
-                    dstate.lights_mode.logo_mode == mode,
+                    format!("Fan: {} RPM", rpm),
+                    dstate.perf_mode != PerfMode::Balanced(FanSpeed::Manual(rpm)),
+                    dstate.perf_mode == PerfMode::Balanced(FanSpeed::Manual(rpm)),
                     None,
                 )
-            })
-            .collect::<Vec<_>>();
+            }))
+            .collect();
+            event_handlers.insert(
+                "fan_speed:auto".to_string(),
+                DeviceState {
+                    perf_mode: PerfMode::Balanced(FanSpeed::Auto),
+                    ..*dstate
+                },
+            );
 
-        menu.append(&Submenu::with_items(
-            "Logo",
-            true,
-            &modes
-                .iter()
-                .map(|i| i as &dyn IsMenuItem)
-                .collect::<Vec<_>>(),
-        )?)?;
-        menu.append(&PredefinedMenuItem::separator())?;
+            perf_modes.append(&Submenu::with_items(
+                "Balanced",
+                true,
+                &fan_speeds
+                    .iter()
+                    .map(|i| i as &dyn IsMenuItem)
+                    .collect::<Vec<_>>(),
+            )?)?;
+
+            // custom
+            let cpu_boosts: Vec<CheckMenuItem> = CpuBoost::iter()
+                .map(|boost| {
+                    let event_id = format!("cpu_boost:{:?}", boost);
+                    event_handlers.insert(event_id.clone(), dstate.delta(boost));
+                    let checked =
+                        matches!(dstate.perf_mode, PerfMode::Custom(b, _, _) if b == boost);
+                    CheckMenuItem::with_id(event_id, format!("{:?}", boost), !checked, checked, None)
+                })
+                .collect();
+
+            let gpu_boosts: Vec<CheckMenuItem> = GpuBoost::iter()
+                .map(|boost| {
+                    let event_id = format!("gpu_boost:{:?}", boost);
+                    event_handlers.insert(event_id.clone(), dstate.delta(boost));
+                    let checked =
+                        matches!(dstate.perf_mode, PerfMode::Custom(_, b, _) if b == boost);
+                    CheckMenuItem::with_id(event_id, format!("{:?}", boost), !checked, checked, None)
+                })
+                .collect();
+
+            let max_fan_speed_mode = &[CheckMenuItem::with_id(
+                "max_fan_speed_mode",
+                "Max Fan Speed",
+                true,
+                matches!(
+                    dstate.perf_mode,
+                    PerfMode::Custom(_, _, MaxFanSpeedMode::Enable)
+                ),
+                None,
+            )];
+            event_handlers.insert(
+                "max_fan_speed_mode".to_string(),
+                match dstate.perf_mode {
+                    PerfMode::Custom(_, _, MaxFanSpeedMode::Enable) => {
+                        dstate.delta(MaxFanSpeedMode::Disable)
+                    }
+                    _ => dstate.delta(MaxFanSpeedMode::Enable),
+                },
+            );
+
+            let separator = PredefinedMenuItem::separator();
+
+            perf_modes.append(&Submenu::with_items(
+                "Custom",
+                true,
+                &cpu_boosts
+                    .iter()
+                    .map(|i| i as &dyn IsMenuItem)
+                    .chain([&separator as &dyn IsMenuItem])
+                    .chain(gpu_boosts.iter().map(|i| i as &dyn IsMenuItem))
+                    .chain([&separator as &dyn IsMenuItem])
+                    .chain(max_fan_speed_mode.iter().map(|i| i as &dyn IsMenuItem))
+                    .collect::<Vec<_>>(),
+            )?)?;
+
+            menu.append(&perf_modes)?;
+        }
+
+        // logo
+        if has_feature(descriptor, "lid-logo") {
+            menu.append(&PredefinedMenuItem::separator())?;
+            let modes = LogoMode::iter()
+                .map(|mode| {
+                    let event_id = format!("logo_mode:{:?}", mode);
+                    event_handlers.insert(
+                        event_id.clone(),
+                        DeviceState {
+                            lights_mode: LightsMode {
+                                logo_mode: mode,
+                                ..dstate.lights_mode
+                            },
+                            ..*dstate
+                        },
+                    );
+                    CheckMenuItem::with_id(
+                        event_id,
+                        format!("{:?}", mode),
+                        dstate.lights_mode.logo_mode != mode,
+                        dstate.lights_mode.logo_mode == mode,
+                        None,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            menu.append(&Submenu::with_items(
+                "Logo",
+                true,
+                &modes
+                    .iter()
+                    .map(|i| i as &dyn IsMenuItem)
+                    .collect::<Vec<_>>(),
+            )?)?;
+        }
 
         // lights always on
-        menu.append(&CheckMenuItem::with_id(
-            "lights_always_on",
-            "Lights always on",
-            true,
-            dstate.lights_mode.always_on == LightsAlwaysOn::Enable,
-            None,
-        ))?;
-        event_handlers.insert(
-            "lights_always_on".to_string(),
-            DeviceState {
-                lights_mode: LightsMode {
-                    always_on: match dstate.lights_mode.always_on {
-                        LightsAlwaysOn::Enable => LightsAlwaysOn::Disable,
-                        LightsAlwaysOn::Disable => LightsAlwaysOn::Enable,
+        if has_feature(descriptor, "lights-always-on") {
+            menu.append(&PredefinedMenuItem::separator())?;
+            menu.append(&CheckMenuItem::with_id(
+                "lights_always_on",
+                "Lights always on",
+                true,
+                dstate.lights_mode.always_on == LightsAlwaysOn::Enable,
+                None,
+            ))?;
+            event_handlers.insert(
+                "lights_always_on".to_string(),
+                DeviceState {
+                    lights_mode: LightsMode {
+                        always_on: match dstate.lights_mode.always_on {
+                            LightsAlwaysOn::Enable => LightsAlwaysOn::Disable,
+                            LightsAlwaysOn::Disable => LightsAlwaysOn::Enable,
+                        },
+                        ..dstate.lights_mode
                     },
-                    ..dstate.lights_mode
+                    ..*dstate
                 },
-                ..*dstate
-            },
-        );
+            );
+        }
 
-        let brightness_modes: Vec<CheckMenuItem> = (0..=100)
-            .step_by(10)
-            .map(|brightness| {
-                let event_id = format!("brightness:{}", brightness);
-                event_handlers.insert(
-                    event_id.clone(),
-                    DeviceState {
-                        lights_mode: LightsMode {
-                            keyboard_brightness: brightness / 2 * 5,
-                            ..dstate.lights_mode
+        if has_feature(descriptor, "kbd-backlight") {
+            let brightness_modes: Vec<CheckMenuItem> = (0..=100)
+                .step_by(10)
+                .map(|brightness| {
+                    let event_id = format!("brightness:{}", brightness);
+                    event_handlers.insert(
+                        event_id.clone(),
+                        DeviceState {
+                            lights_mode: LightsMode {
+                                keyboard_brightness: brightness / 2 * 5,
+                                ..dstate.lights_mode
+                            },
+                            ..*dstate
                         },
-                        ..*dstate
-                    },
-                );
-                CheckMenuItem::with_id(
-                    event_id,
-                    format!("Brightness: {}", brightness),
-                    dstate.lights_mode.keyboard_brightness != brightness / 2 * 5,
-                    dstate.lights_mode.keyboard_brightness == brightness / 2 * 5,
+                    );
+                    CheckMenuItem::with_id(
+                        event_id,
+                        format!("Brightness: {}", brightness),
+                        dstate.lights_mode.keyboard_brightness != brightness / 2 * 5,
+                        dstate.lights_mode.keyboard_brightness == brightness / 2 * 5,
+                        None,
+                    )
+                })
+                .collect();
+
+            menu.append(&Submenu::with_items(
+                "Brightness",
+                true,
+                &brightness_modes
+                    .iter()
+                    .map(|i| i as &dyn IsMenuItem)
+                    .collect::<Vec<_>>(),
+            )?)?;
+        }
+
+        // battery health optimizer
+        if has_feature(descriptor, "battery-care") {
+            menu.append_items(&[
+                &PredefinedMenuItem::separator(),
+                &CheckMenuItem::with_id(
+                    "bho",
+                    "Battery Health Optimizer",
+                    true,
+                    dstate.battery_care == BatteryCare::Enable,
                     None,
-                )
+                ),
+            ])?;
+            event_handlers.insert(
+                "bho".to_string(),
+                DeviceState {
+                    battery_care: match dstate.battery_care {
+                        BatteryCare::Enable => BatteryCare::Disable,
+                        BatteryCare::Disable => BatteryCare::Enable,
+                    },
+                    ..*dstate
+                },
+            );
+        }
+
+        // log window
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&CheckMenuItem::with_id(
+            "show_log_window",
+            "Show Log Window",
+            true,
+            log_window_visible,
+            None,
+        ))?;
+
+        // profiles
+        // Selecting, saving, and deleting a profile (and showing the log window, above) are
+        // handled directly by id in `main` rather than through `event_handlers`, since they don't
+        // map to a `DeviceState`.
+        let mut profile_names: Vec<&String> = profiles.profiles.keys().collect();
+        profile_names.sort();
+
+        let profile_items: Vec<CheckMenuItem> = profile_names
+            .iter()
+            .map(|name| {
+                let active = profiles.active.as_deref() == Some(name.as_str());
+                CheckMenuItem::with_id(format!("profile:{name}"), name.as_str(), !active, active, None)
             })
             .collect();
 
+        let delete_items: Vec<MenuItem> = profile_names
+            .iter()
+            .map(|name| MenuItem::with_id(format!("profile_delete:{name}"), name.as_str(), true, None))
+            .collect();
+
+        let save_item = MenuItem::with_id("profile_save", "Save current as new profile", true, None);
+        let profiles_separator = PredefinedMenuItem::separator();
+
+        let on_ac_items: Vec<CheckMenuItem> = profile_names
+            .iter()
+            .map(|name| {
+                let bound = profiles.on_ac.as_deref() == Some(name.as_str());
+                CheckMenuItem::with_id(format!("bind_on_ac:{name}"), name.as_str(), true, bound, None)
+            })
+            .collect();
+        let on_battery_items: Vec<CheckMenuItem> = profile_names
+            .iter()
+            .map(|name| {
+                let bound = profiles.on_battery.as_deref() == Some(name.as_str());
+                CheckMenuItem::with_id(format!("bind_on_battery:{name}"), name.as_str(), true, bound, None)
+            })
+            .collect();
+
+        menu.append(&PredefinedMenuItem::separator())?;
         menu.append(&Submenu::with_items(
-            "Brightness",
+            "Profiles",
             true,
-            &brightness_modes
+            &profile_items
                 .iter()
                 .map(|i| i as &dyn IsMenuItem)
+                .chain([&profiles_separator as &dyn IsMenuItem])
+                .chain([&save_item as &dyn IsMenuItem])
+                .chain([&Submenu::with_items(
+                    "Delete",
+                    !delete_items.is_empty(),
+                    &delete_items
+                        .iter()
+                        .map(|i| i as &dyn IsMenuItem)
+                        .collect::<Vec<_>>(),
+                )? as &dyn IsMenuItem])
+                .chain([&Submenu::with_items(
+                    "Use on AC power",
+                    !on_ac_items.is_empty(),
+                    &on_ac_items
+                        .iter()
+                        .map(|i| i as &dyn IsMenuItem)
+                        .collect::<Vec<_>>(),
+                )? as &dyn IsMenuItem])
+                .chain([&Submenu::with_items(
+                    "Use on battery",
+                    !on_battery_items.is_empty(),
+                    &on_battery_items
+                        .iter()
+                        .map(|i| i as &dyn IsMenuItem)
+                        .collect::<Vec<_>>(),
+                )? as &dyn IsMenuItem])
                 .collect::<Vec<_>>(),
         )?)?;
 
-        // battery health optimizer
-        menu.append_items(&[
-            &PredefinedMenuItem::separator(),
-            &CheckMenuItem::with_id(
-                "bho",
-                "Battery Health Optimizer",
-                true,
-                dstate.battery_care == BatteryCare::Enable,
-                None,
-            ),
-        ])?;
-        event_handlers.insert(
-            "bho".to_string(),
-            DeviceState {
-                battery_care: match dstate.battery_care {
-                    BatteryCare::Enable => BatteryCare::Disable,
-                    BatteryCare::Disable => BatteryCare::Enable,
-                },
-                ..*dstate
-            },
-        );
-
         // footer
         menu.append(&PredefinedMenuItem::separator())?;
         menu.append(&PredefinedMenuItem::about(None, Some(Self::about())))?;
@@ -512,6 +760,15 @@ impl ProgramState {
             status.push('🔋');
         }
 
+        if let Some(battery) = self.battery {
+            writeln!(
+                &mut info,
+                "🔋 {}%{}",
+                battery.percent,
+                if battery.charging { " (charging)" } else { "" }
+            )?;
+        }
+
         Ok((info.to_string() + &status).trim_end().to_string())
     }
 
@@ -520,7 +777,13 @@ impl ProgramState {
         let razer_yellow = include_bytes!("../icons/razer-yellow.png");
         let razer_green = include_bytes!("../icons/razer-green.png");
 
+        // A critically low, non-charging battery takes priority over the perf-mode color: it's
+        // the more urgent thing to notice at a glance. There's no overlay asset in this tree to
+        // show both signals at once, so the other thresholds (green/yellow) stay perf-mode driven.
+        let low_battery = matches!(self.battery, Some(b) if b.percent < 20 && !b.charging);
+
         let image = match self.device_state.perf_mode {
+            _ if low_battery => image::load_from_memory(razer_red),
             PerfMode::Silent => image::load_from_memory(razer_yellow),
             PerfMode::Balanced(_) => image::load_from_memory(razer_green),
             PerfMode::Custom(_, _, _) => image::load_from_memory(razer_red),
@@ -540,8 +803,15 @@ fn update(
     tray_icon: &mut tray_icon::TrayIcon,
     new_device_state: DeviceState,
     device: &device::Device,
+    profiles: Profiles,
+    log_window_visible: bool,
 ) -> Result<ProgramState> {
-    let new_program_state = ProgramState::new(new_device_state)?;
+    let new_program_state = ProgramState::new(
+        new_device_state,
+        profiles,
+        log_window_visible,
+        device.info().clone(),
+    )?;
     tray_icon.set_icon(Some(new_program_state.icon()))?;
     tray_icon.set_tooltip(Some(new_program_state.tooltip()?))?;
     tray_icon.set_menu(Some(Box::new(new_program_state.menu.clone())));
@@ -557,7 +827,9 @@ fn get_logging_file_path() -> std::path::PathBuf {
     std::env::temp_dir().join(format!("{}.log", PKG_NAME))
 }
 
-fn init_logging_to_file() -> Result<()> {
+/// Builds the logging config: always the rolling file, plus a console appender when the "Show
+/// Log Window" menu item is checked, so log lines stream live while the window is visible.
+fn build_logging_config(with_console: bool) -> Result<log4rs::config::Config> {
     use log4rs::append::rolling_file::policy::compound::{
         roll::delete::DeleteRoller, trigger::size::SizeTrigger, CompoundPolicy,
     };
@@ -565,23 +837,62 @@ fn init_logging_to_file() -> Result<()> {
         Box::new(SizeTrigger::new(50 << 20)),
         Box::new(DeleteRoller::new()),
     );
+    let pattern = "{h({d(%Y-%m-%d %H:%M:%S)(local)} - {l}: {m}{n})}";
 
     let logfile = log4rs::append::rolling_file::RollingFileAppender::builder()
         .encoder(Box::new(log4rs::encode::pattern::PatternEncoder::new(
-            "{h({d(%Y-%m-%d %H:%M:%S)(local)} - {l}: {m}{n})}",
+            pattern,
         )))
         .build(get_logging_file_path(), Box::new(policy))?;
 
-    let config = log4rs::config::Config::builder()
-        .appender(log4rs::config::Appender::builder().build("logfile", Box::new(logfile)))
-        .build(
-            log4rs::config::Root::builder()
-                .appender("logfile")
-                .build(log::LevelFilter::Trace),
-        )?;
+    let mut config = log4rs::config::Config::builder()
+        .appender(log4rs::config::Appender::builder().build("logfile", Box::new(logfile)));
+    let mut root_appenders = vec!["logfile".to_string()];
+
+    if with_console {
+        let console = log4rs::append::console::ConsoleAppender::builder()
+            .encoder(Box::new(log4rs::encode::pattern::PatternEncoder::new(
+                pattern,
+            )))
+            .build();
+        config =
+            config.appender(log4rs::config::Appender::builder().build("console", Box::new(console)));
+        root_appenders.push("console".to_string());
+    }
+
+    Ok(config.build(
+        log4rs::config::Root::builder()
+            .appenders(root_appenders)
+            .build(log::LevelFilter::Trace),
+    )?)
+}
 
-    log4rs::init_config(config)?;
-    Ok(())
+fn init_logging_to_file() -> Result<log4rs::Handle> {
+    Ok(log4rs::init_config(build_logging_config(false)?)?)
+}
+
+/// Wakeups sent from the background ticker threads in `main`, so the event loop can sit on
+/// `ControlFlow::Wait` instead of polling every second.
+#[derive(Debug, Clone, Copy)]
+enum UserEvent {
+    CheckDeviceState,
+    CheckBattery,
+    CheckPowerSource,
+}
+
+/// Sleeps for `interval`, then wakes the event loop with `event`, forever. Exits once the event
+/// loop (and so `proxy`) is gone.
+fn spawn_ticker(
+    proxy: tao::event_loop::EventLoopProxy<UserEvent>,
+    interval: std::time::Duration,
+    event: UserEvent,
+) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        if proxy.send_event(event).is_err() {
+            break;
+        }
+    });
 }
 
 fn init(tray_icon: &mut tray_icon::TrayIcon, device: &device::Device) -> Result<ProgramState> {
@@ -589,14 +900,16 @@ fn init(tray_icon: &mut tray_icon::TrayIcon, device: &device::Device) -> Result<
         "loading config file {}",
         confy::get_configuration_file_path(PKG_NAME, None)?.display()
     );
-    let config = confy::load(PKG_NAME, None).unwrap_or_default();
-    let state = ProgramState::new(config)?;
+    let device_state = confy::load(PKG_NAME, None).unwrap_or_default();
+    let profiles = confy::load(PKG_NAME, Some(PROFILES_CONFIG_NAME)).unwrap_or_default();
 
-    update(tray_icon, state.device_state, device)
+    // The log window always starts closed; it's an in-process debugging aid, not persisted
+    // device/profile state.
+    update(tray_icon, device_state, device, profiles, false)
 }
 
 fn main() -> Result<()> {
-    init_logging_to_file()?;
+    let log4rs_handle = init_logging_to_file()?;
     log::info!("{0} starting {1} {0}", "==".repeat(20), PKG_NAME);
 
     let device = match device::Device::detect() {
@@ -624,31 +937,164 @@ fn main() -> Result<()> {
 
     let menu_channel = MenuEvent::receiver();
     let tray_channel = TrayIconEvent::receiver();
-    let event_loop = EventLoopBuilder::new().build();
+    let event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
+
+    spawn_ticker(
+        event_loop.create_proxy(),
+        std::time::Duration::from_secs(20),
+        UserEvent::CheckDeviceState,
+    );
+    spawn_ticker(
+        event_loop.create_proxy(),
+        std::time::Duration::from_secs(60),
+        UserEvent::CheckBattery,
+    );
+    spawn_ticker(
+        event_loop.create_proxy(),
+        std::time::Duration::from_secs(20),
+        UserEvent::CheckPowerSource,
+    );
 
-    let mut last_device_state_check_timestamp = std::time::Instant::now();
+    // Like `librazer::service::watch`, the first reading always counts as a transition, so a
+    // bound profile is applied once on startup rather than waiting for the next AC/battery change.
+    let mut last_on_ac: Option<bool> = None;
 
-    event_loop.run(move |_, _, control_flow| {
-        let now = std::time::Instant::now();
-        *control_flow = ControlFlow::WaitUntil(now + std::time::Duration::from_millis(1000));
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
 
         if let Err(e) = (|| -> Result<()> {
-            if let Ok(event) = menu_channel.try_recv() {
-                state = update(&mut tray_icon, state.handle_event(event.id.as_ref())?, &device)?;
+            if let Ok(menu_event) = menu_channel.try_recv() {
+                let id = menu_event.id.as_ref();
+                if id == "profile_save" {
+                    let profiles = state.save_current_profile()?;
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        profiles,
+                        state.log_window_visible,
+                    )?;
+                } else if let Some(name) = id.strip_prefix("profile_delete:") {
+                    let profiles = state.delete_profile(name)?;
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        profiles,
+                        state.log_window_visible,
+                    )?;
+                } else if let Some(name) = id.strip_prefix("profile:") {
+                    let (device_state, profiles) = state.activate_profile(name)?;
+                    state = update(
+                        &mut tray_icon,
+                        device_state,
+                        &device,
+                        profiles,
+                        state.log_window_visible,
+                    )?;
+                } else if let Some(name) = id.strip_prefix("bind_on_ac:") {
+                    let profiles = state.bind_on_ac(name)?;
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        profiles,
+                        state.log_window_visible,
+                    )?;
+                } else if let Some(name) = id.strip_prefix("bind_on_battery:") {
+                    let profiles = state.bind_on_battery(name)?;
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        profiles,
+                        state.log_window_visible,
+                    )?;
+                } else if id == "show_log_window" {
+                    let visible = !state.log_window_visible;
+                    console::set_visible(visible)?;
+                    log4rs_handle.set_config(build_logging_config(visible)?);
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        state.profiles.clone(),
+                        visible,
+                    )?;
+                } else {
+                    state = update(
+                        &mut tray_icon,
+                        state.handle_event(id)?,
+                        &device,
+                        state.profiles.clone(),
+                        state.log_window_visible,
+                    )?;
+                }
             }
 
             if matches!(tray_channel.try_recv(), Ok(event) if event.click_type == tray_icon::ClickType::Left) {
-                state = update(&mut tray_icon, state.get_next_perf_mode(), &device)?;
+                state = update(
+                    &mut tray_icon,
+                    state.get_next_perf_mode(),
+                    &device,
+                    state.profiles.clone(),
+                    state.log_window_visible,
+                )?;
             }
 
-            if now > last_device_state_check_timestamp + std::time::Duration::from_secs(20)
-            {
-                last_device_state_check_timestamp = now;
+            if matches!(event, Event::UserEvent(UserEvent::CheckDeviceState)) {
                 let active_device_state = DeviceState::read(&device)?;
                 if active_device_state != state.device_state {
                     log::warn!("overriding externally modified state {:?},",
                               active_device_state);
-                    state = update(&mut tray_icon, state.device_state, &device)?;
+                    state = update(
+                        &mut tray_icon,
+                        state.device_state,
+                        &device,
+                        state.profiles.clone(),
+                        state.log_window_visible,
+                    )?;
+                }
+            }
+
+            // Battery status comes from the OS, not the device, so refreshing it here never
+            // touches the HID channel.
+            if matches!(event, Event::UserEvent(UserEvent::CheckBattery)) {
+                state = state.with_battery(battery::read().ok());
+                tray_icon.set_icon(Some(state.icon()))?;
+                tray_icon.set_tooltip(Some(state.tooltip()?))?;
+            }
+
+            if matches!(event, Event::UserEvent(UserEvent::CheckPowerSource)) {
+                if let Ok(battery) = battery::read() {
+                    let on_ac = battery.on_ac;
+                    if last_on_ac != Some(on_ac) {
+                        last_on_ac = Some(on_ac);
+                        let bound = if on_ac {
+                            state.profiles.on_ac.clone()
+                        } else {
+                            state.profiles.on_battery.clone()
+                        };
+                        if let Some(name) = bound {
+                            match state.activate_profile(&name) {
+                                Ok((device_state, profiles)) => {
+                                    log::info!(
+                                        "power source changed (on_ac={}), applying profile {:?}",
+                                        on_ac,
+                                        name
+                                    );
+                                    state = update(
+                                        &mut tray_icon,
+                                        device_state,
+                                        &device,
+                                        profiles,
+                                        state.log_window_visible,
+                                    )?;
+                                }
+                                Err(e) => log::warn!("bound profile {:?} is gone: {:?}", name, e),
+                            }
+                        }
+                    }
                 }
             }
 